@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use rabex_env::handle::SerializedFileHandle;
+use rabex_env::rabex::objects::PPtr;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::enums::CurrencyType;
+use crate::generated::{CollectableItemRelicType, Quest, ShopItem};
+use crate::scenes::{ObjectKind, Scene};
+use crate::typetree;
+use crate::warning::Warning;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum SourceKind {
+    Pickup,
+    Shop,
+    QuestReward,
+    /// Reward of a quest for killing enemies recorded in the journal.
+    JournalReward,
+    RelicReward,
+}
+
+/// One way of obtaining an item.
+#[derive(Debug, Serialize)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// The scene pickup, shop item, quest or relic type the item comes from.
+    pub from: String,
+    pub scene: Option<String>,
+    pub count: i32,
+    pub cost: Option<i32>,
+    pub currency: Option<CurrencyType>,
+    pub condition: Option<String>,
+}
+
+/// Every known source of each `SavedItem`, keyed by its name.
+///
+/// Currencies are keyed by their [`CurrencyType`], e.g. `Money`.
+/// Scenes have to be added before shop lists, shop items and relic types, so these can be linked to the scenes
+/// selling or paying for them.
+#[derive(Debug, Default, Serialize)]
+pub struct AcquisitionIndex {
+    pub items: IndexMap<String, Vec<Source>>,
+    /// Scenes of the shops pointing to each shop item or item list, by name.
+    #[serde(skip)]
+    shop_scenes: HashMap<String, Vec<String>>,
+    /// Scene and `currencyType` of every relic board.
+    #[serde(skip)]
+    relic_boards: Vec<(String, CurrencyType)>,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct JournalTarget {
+    m_Name: String,
+    /// Only present on `EnemyJournalRecord`s.
+    killsRequired: Option<i32>,
+}
+
+impl AcquisitionIndex {
+    fn add(&mut self, item: String, source: Source) {
+        self.items.entry(item).or_default().push(source);
+    }
+
+    /// Adds the pickups of `scene` and remembers its shops and relic boards. Relic boards without a known
+    /// `currencyType` are reported in `warnings`.
+    pub fn add_scene(&mut self, scene: &Scene, warnings: &mut Vec<Warning>) {
+        for pickup in scene.objects_of(ObjectKind::Pickup) {
+            let Some(item) = &pickup.item else {
+                continue;
+            };
+            self.add(
+                item.clone(),
+                Source {
+                    kind: SourceKind::Pickup,
+                    from: pickup.path.clone(),
+                    scene: Some(scene.name.clone()),
+                    count: 1,
+                    cost: None,
+                    currency: None,
+                    condition: None,
+                },
+            );
+        }
+
+        for shop in scene.objects_of(ObjectKind::Shop) {
+            for stock in &shop.stock {
+                let scenes = self.shop_scenes.entry(stock.clone()).or_default();
+                if !scenes.contains(&scene.name) {
+                    scenes.push(scene.name.clone());
+                }
+            }
+        }
+
+        for board in scene.objects_of(ObjectKind::RelicBoard) {
+            let currency = board.data["currencyType"]
+                .as_i64()
+                .and_then(|currency| CurrencyType::try_from(currency as i32).ok());
+            match currency {
+                Some(currency) => self.relic_boards.push((scene.name.clone(), currency)),
+                None => warnings.push(Warning::new(
+                    format!("{}/{}", scene.name, board.path),
+                    format!(
+                        "relic board {} has no known currencyType: {}",
+                        board.script, board.data["currencyType"]
+                    ),
+                )),
+            }
+        }
+    }
+
+    /// Registers every `ShopItemList` in `file`, so shops selling from a list are linked to the list's items.
+    ///
+    /// Entries which can't be read as a `ShopItem` are skipped and reported in `warnings`.
+    pub fn add_shop_lists(
+        &mut self,
+        file: SerializedFileHandle,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<()> {
+        for object in file.scripts::<Value>(&"ShopItemList")? {
+            let list = object.read()?;
+            let name = list["m_Name"].as_str().unwrap_or_default();
+            let mut items = Vec::new();
+            for (field, pptr) in typetree::object_pptrs(&list) {
+                match file.deref_read_optional(pptr.typed::<ShopItem>()) {
+                    Ok(Some(item)) => items.push(item.m_Name),
+                    Ok(None) => {}
+                    Err(e) => warnings.push(Warning::new(
+                        name,
+                        format!("couldn't read shop item {field}: {e:#}"),
+                    )),
+                }
+            }
+            self.add_shop_list(name, &items);
+        }
+        Ok(())
+    }
+
+    /// Links the shop items of the list `name` to the scenes of the shops selling from it.
+    pub fn add_shop_list(&mut self, name: &str, items: &[String]) {
+        let Some(scenes) = self.shop_scenes.get(name).cloned() else {
+            return;
+        };
+        for item in items {
+            let item_scenes = self.shop_scenes.entry(item.clone()).or_default();
+            for scene in &scenes {
+                if !item_scenes.contains(scene) {
+                    item_scenes.push(scene.clone());
+                }
+            }
+        }
+    }
+
+    /// Adds the reward of `quest`, as [`SourceKind::JournalReward`] if its targets are journal records.
+    pub fn add_quest(&mut self, file: SerializedFileHandle, quest: &Quest) -> Result<()> {
+        let Some(item) = file.deref_read_optional(quest.rewardItem)? else {
+            return Ok(());
+        };
+
+        let mut journal_targets = Vec::new();
+        for target in &quest.targets {
+            let counter = PPtr {
+                m_FileID: target.Counter.m_FileID,
+                m_PathID: target.Counter.m_PathID,
+            };
+            if counter.m_PathID == 0 {
+                continue;
+            }
+            let counter = file.deref_read_optional(counter.typed::<JournalTarget>())?;
+            if let Some(JournalTarget {
+                m_Name,
+                killsRequired: Some(_),
+            }) = counter
+            {
+                journal_targets.push(format!("{} {m_Name}", target.Count));
+            }
+        }
+
+        self.add_quest_reward(quest, item.m_Name, &journal_targets);
+        Ok(())
+    }
+
+    /// Adds `item` as the reward of `quest`. `journal_targets` are the enemies to kill, like `3 Mossgrub`.
+    pub fn add_quest_reward(&mut self, quest: &Quest, item: String, journal_targets: &[String]) {
+        let (kind, condition) = match journal_targets.is_empty() {
+            true => (
+                SourceKind::QuestReward,
+                non_empty(quest.playerDataTest.to_string()),
+            ),
+            false => (
+                SourceKind::JournalReward,
+                Some(format!("kill {}", journal_targets.join(", "))),
+            ),
+        };
+        self.add(
+            item,
+            Source {
+                kind,
+                from: quest.m_Name.clone(),
+                scene: None,
+                count: quest.rewardCount,
+                cost: None,
+                currency: None,
+                condition,
+            },
+        );
+    }
+
+    /// Adds the item sold by `shop_item` once per scene selling it. Shop items without a `savedItem` are skipped.
+    pub fn add_shop_item(
+        &mut self,
+        file: SerializedFileHandle,
+        shop_item: &ShopItem,
+    ) -> Result<()> {
+        let Some(item) = file.deref_read_optional(shop_item.savedItem)? else {
+            return Ok(());
+        };
+        let cost = file
+            .deref_read_optional(shop_item.costReference)?
+            .map(|cost| cost.value)
+            .unwrap_or(shop_item.cost);
+        self.add_shop_item_source(shop_item, item.m_Name, cost);
+        Ok(())
+    }
+
+    /// Adds `item` as sold by `shop_item` for `cost`, once per scene selling it.
+    pub fn add_shop_item_source(&mut self, shop_item: &ShopItem, item: String, cost: i32) {
+        let currency = CurrencyType::try_from(shop_item.currencyType).ok();
+
+        let scenes = match self.shop_scenes.get(&shop_item.m_Name) {
+            Some(scenes) => scenes.iter().cloned().map(Some).collect(),
+            None => vec![None],
+        };
+        for scene in scenes {
+            self.add(
+                item.clone(),
+                Source {
+                    kind: SourceKind::Shop,
+                    from: shop_item.m_Name.clone(),
+                    scene,
+                    count: 1,
+                    cost: Some(cost),
+                    currency,
+                    condition: non_empty(shop_item.extraAppearConditions.to_string()),
+                },
+            );
+        }
+    }
+
+    /// Adds the `rewardAmount` paid for a relic of `relic_type`, in the currency of every relic board.
+    ///
+    /// Relic types don't store the currency, so without a relic board the reward is reported in `warnings`.
+    pub fn add_relic_type(
+        &mut self,
+        relic_type: &CollectableItemRelicType,
+        warnings: &mut Vec<Warning>,
+    ) {
+        if self.relic_boards.is_empty() {
+            warnings.push(Warning::new(
+                &relic_type.m_Name,
+                "no relic board found, so the currency of the reward is unknown",
+            ));
+        }
+        for (scene, currency) in self.relic_boards.clone() {
+            self.add(
+                format!("{currency:?}"),
+                Source {
+                    kind: SourceKind::RelicReward,
+                    from: relic_type.m_Name.clone(),
+                    scene: Some(scene),
+                    count: relic_type.rewardAmount,
+                    cost: None,
+                    currency: Some(currency),
+                    condition: None,
+                },
+            );
+        }
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    (!s.is_empty()).then_some(s)
+}
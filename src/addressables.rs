@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rabex_env::Environment;
+
+/// Directory of the data asset bundles, relative to the [`build_dir`].
+pub const DATA_ASSETS_DIR: &str = "dataassets_assets_assets/dataassets";
+/// Directory of the scene bundles, relative to the [`build_dir`].
+pub const SCENES_DIR: &str = "scenes_scenes_scenes";
+
+/// The platform specific directory containing the addressable bundles, e.g. `StreamingAssets/aa/StandaloneWindows64`.
+pub fn build_dir(env: &Environment) -> Result<PathBuf> {
+    let aa = env.game_files.game_dir.join("StreamingAssets/aa");
+    for entry in std::fs::read_dir(&aa).with_context(|| format!("Reading {}", aa.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            return Ok(path);
+        }
+    }
+    anyhow::bail!("No addressables build directory in {}", aa.display())
+}
+
+/// All bundles below `dir`, sorted and relative to the [`build_dir`], as accepted by `load_addressables_bundle_content`.
+pub fn bundles_in(env: &Environment, dir: &Path) -> Result<Vec<PathBuf>> {
+    let build_dir = build_dir(env)?;
+    let mut bundles = Vec::new();
+    collect_bundles(&build_dir, &build_dir.join(dir), &mut bundles)?;
+    bundles.sort();
+    Ok(bundles)
+}
+
+fn collect_bundles(base: &Path, dir: &Path, bundles: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_bundles(base, &path, bundles)?;
+        } else if path.extension().is_some_and(|ext| ext == "bundle") {
+            bundles.push(path.strip_prefix(base)?.to_owned());
+        }
+    }
+    Ok(())
+}
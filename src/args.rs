@@ -0,0 +1,66 @@
+//! Command line parsing shared by the binaries.
+//!
+//! Arguments are `--flag`s, `--option <VALUE>`s, which may be repeated, and positional arguments.
+
+use anyhow::{Context, Result, bail};
+
+#[derive(Debug, Default)]
+pub struct Args {
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    /// Parses the arguments of the current process, see [`Args::parse`].
+    pub fn from_env(flags: &[&str], options: &[&str]) -> Result<Args> {
+        Args::parse(std::env::args().skip(1), flags, options)
+    }
+
+    /// `flags` and `options` are the accepted names, e.g. `"--force"` or `"--lang"`.
+    /// Any other argument starting with `--` is an error.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        flags: &[&str],
+        options: &[&str],
+    ) -> Result<Args> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if options.contains(&arg.as_str()) {
+                let value = args
+                    .next()
+                    .with_context(|| format!("{arg} requires a value"))?;
+                parsed.options.push((arg, value));
+            } else if flags.contains(&arg.as_str()) {
+                parsed.flags.push(arg);
+            } else if arg.starts_with("--") {
+                bail!("unknown argument '{arg}'");
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// The value of the option, the last one if it was given multiple times.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options_of(name).last()
+    }
+
+    /// Every value of a repeatable option, in order.
+    pub fn options_of<'a>(&'a self, name: &str) -> impl DoubleEndedIterator<Item = &'a str> {
+        self.options
+            .iter()
+            .filter(move |(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use silksong_data_dump::args::Args;
+use silksong_data_dump::catalog::Catalog;
+use silksong_data_dump::metadata::GameMetadata;
+
+/// Usage: `catalog [--directory]`
+///
+/// Writes every addressable bundle with its dependencies, assets and script counts to `out/catalog.json`.
+/// With `--directory` the bundles of the build directory are listed instead of those of the catalog.
+fn main() -> Result<()> {
+    let directory = Args::from_env(&["--directory"], &[])?.flag("--directory");
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+
+    let catalog = match directory {
+        true => Catalog::read_directory(&env)?,
+        false => Catalog::read(&env)
+            .context("Reading the catalog failed, pass --directory to list the build directory")?,
+    };
+    for warning in &catalog.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let out = Path::new("out");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    std::fs::write(
+        out.join("catalog.json"),
+        serde_json::to_string_pretty(&catalog)?,
+    )?;
+
+    let monobehaviours: usize = catalog
+        .bundles
+        .iter()
+        .flat_map(|bundle| bundle.scripts.values())
+        .sum();
+    println!(
+        "{} bundles from {}, {monobehaviours} MonoBehaviours",
+        catalog.bundles.len(),
+        catalog.source
+    );
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rabex_env::handle::ScriptFilterContains;
+use silksong_data_dump::addressables;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::completion::CompletionModel;
+use silksong_data_dump::generated::{EnemyJournalRecord, Quest, ToolItemBasic};
+use silksong_data_dump::metadata::GameMetadata;
+
+/// Usage: `completion [decoded-save.json] [--steel-soul]`
+fn main() -> Result<()> {
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+
+    let args = Args::from_env(&["--steel-soul"], &[])?;
+    let steel_soul = args.flag("--steel-soul");
+    let save = args.positional().first();
+
+    let data_assets = Path::new(addressables::DATA_ASSETS_DIR);
+    let mut model = CompletionModel::default();
+    let mut warnings = Vec::new();
+
+    let file = env
+        .load_addressables_bundle_content(data_assets.join("enemyjournal/journalrecords.bundle"))?;
+    for record in file.scripts::<EnemyJournalRecord>(&"EnemyJournalRecord")? {
+        model.add_journal_record(&record.read()?, steel_soul, &mut warnings);
+    }
+    let file = env.load_addressables_bundle_content(data_assets.join("tools/toolitems.bundle"))?;
+    for tool in file.scripts::<ToolItemBasic>(&ScriptFilterContains("Tool"))? {
+        model.add_tool(&tool.read()?);
+    }
+    let file =
+        env.load_addressables_bundle_content(data_assets.join("questsystem/quests.bundle"))?;
+    for quest in file.scripts::<Quest>(&"Quest")? {
+        model.add_quest(&quest.read()?);
+    }
+
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let out = Path::new("out");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    std::fs::write(
+        out.join("completion.json"),
+        serde_json::to_string_pretty(&model)?,
+    )?;
+
+    if let Some(save) = save {
+        let save: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(save).with_context(|| format!("Reading {save}"))?,
+        )?;
+        let player_data = save.get("playerData").unwrap_or(&save);
+        let progress = model.progress(player_data);
+        println!("{}", serde_json::to_string_pretty(&progress)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use silksong_data_dump::addressables;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::enemies::EnemyIndex;
+use silksong_data_dump::generated::EnemyJournalRecord;
+use silksong_data_dump::metadata::GameMetadata;
+
+fn main() -> Result<()> {
+    let args = Args::from_env(&[], &[])?;
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+
+    let out = Path::new("out");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+
+    // scenes by default, prefab bundle directories can be passed as arguments
+    let mut dirs: Vec<&str> = args.positional().iter().map(String::as_str).collect();
+    if dirs.is_empty() {
+        dirs.push(addressables::SCENES_DIR);
+    }
+
+    let mut enemies = EnemyIndex::default();
+    for dir in &dirs {
+        for bundle in addressables::bundles_in(&env, Path::new(dir))? {
+            let source = bundle.file_stem().unwrap().to_string_lossy();
+            let file = env.load_addressables_bundle_content(&bundle)?;
+            enemies
+                .add_file(&source, file)
+                .with_context(|| format!("Scanning {}", bundle.display()))?;
+        }
+    }
+
+    let journal = env.load_addressables_bundle_content(
+        "dataassets_assets_assets/dataassets/enemyjournal/journalrecords.bundle",
+    )?;
+    let mut records = Vec::new();
+    for record in journal.scripts::<EnemyJournalRecord>(&"EnemyJournalRecord")? {
+        records.push(record.read()?);
+    }
+    enemies.link_journal(&records);
+
+    let enemies: Vec<_> = enemies.enemies.values().collect();
+    std::fs::write(
+        out.join("enemies.json"),
+        serde_json::to_string_pretty(&enemies)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use silksong_data_dump::addressables;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::fsm::Fsm;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::playerdata::PlayerDataIndex;
+use silksong_data_dump::tables::TableWriter;
+
+/// Usage: `dump_fsms [--version-column] [<DIR>]...`
+///
+/// `--version-column` appends a `gameVersion` column to `out/playerdata_fsms.csv`.
+fn main() -> Result<()> {
+    let args = Args::from_env(&["--version-column"], &[])?;
+    let version_column = args.flag("--version-column");
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+
+    let out = Path::new("out/fsms");
+    let _ = std::fs::remove_dir_all(out);
+    std::fs::create_dir_all(out)?;
+    // the FSMs and `out/playerdata_fsms.csv` are one dump, described by `out/metadata.json`
+    let metadata = GameMetadata::read(&env, Some(steam_app))?;
+    metadata.write(Path::new("out"))?;
+    let version = version_column.then(|| metadata.version_string());
+
+    // scenes by default, prefab bundle directories can be passed as arguments
+    let mut dirs: Vec<&str> = args.positional().iter().map(String::as_str).collect();
+    if dirs.is_empty() {
+        dirs.push(addressables::SCENES_DIR);
+    }
+
+    let mut player_data = PlayerDataIndex::default();
+    for dir in &dirs {
+        for bundle in addressables::bundles_in(&env, Path::new(dir))? {
+            let name = bundle.file_stem().unwrap().to_string_lossy();
+            let file = env.load_addressables_bundle_content(&bundle)?;
+            let mut warnings = Vec::new();
+            let fsms = Fsm::read_all(file, &mut warnings)
+                .with_context(|| format!("Reading FSMs of {}", bundle.display()))?;
+            for warning in warnings {
+                eprintln!("warning: {name}: {warning}");
+            }
+            if fsms.is_empty() {
+                continue;
+            }
+
+            let dir = out.join(&*name);
+            std::fs::create_dir_all(&dir)?;
+            for fsm in fsms {
+                player_data.add_fsm(&fsm, &name);
+
+                let file_name = sanitize(&format!("{}-{}", fsm.gameObject, fsm.name));
+                std::fs::write(
+                    dir.join(&file_name).with_extension("json"),
+                    serde_json::to_string_pretty(&fsm)?,
+                )?;
+                std::fs::write(dir.join(&file_name).with_extension("dot"), fsm.to_dot())?;
+            }
+        }
+    }
+
+    let path = Path::new("out/playerdata_fsms.csv");
+    let mut writer = TableWriter::create(path, version.as_deref())?;
+    for row in player_data.table() {
+        writer.serialize(row)?;
+    }
+    writer.into_inner()?;
+
+    Ok(())
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '.' => '_',
+            c => c,
+        })
+        .collect()
+}
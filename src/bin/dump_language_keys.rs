@@ -1,16 +1,45 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use silksong_data_dump::lang;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::lang::{self, LanguageConfig};
+use silksong_data_dump::metadata::GameMetadata;
 
+/// Usage: `dump_language_keys [--key <KEY>]... [--prefix <PREFIX>] [--bundle-dir <DIR>]...`
+///
+/// Additional keys are tried after the default one. Language assets are also searched in all bundles, or only in
+/// those of every `--bundle-dir`, relative to the addressables build directory. The file each sheet was loaded from
+/// is written to `out/languages/origins.json`.
 fn main() -> Result<()> {
-    let env = silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let args = Args::from_env(&[], &["--key", "--prefix", "--bundle-dir"])?;
+    if let Some(arg) = args.positional().first() {
+        anyhow::bail!("unknown argument '{arg}'");
+    }
+    let mut config = LanguageConfig::default();
+    config
+        .keys
+        .extend(args.options_of("--key").map(|key| key.as_bytes().to_vec()));
+    if let Some(prefix) = args.option("--prefix") {
+        config.prefix = prefix.to_owned();
+    }
+    let bundle_dirs: Vec<_> = args.options_of("--bundle-dir").map(PathBuf::from).collect();
+    if !bundle_dirs.is_empty() {
+        config.bundle_dirs = bundle_dirs;
+    }
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
 
     let out = Path::new("out/languages");
     let _ = std::fs::remove_dir_all(out);
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
 
-    let lang = lang::get_language_keys(&env)?;
-    for (lang, sheets) in lang {
+    let loaded = lang::load_languages(&env, &config)?;
+    for warning in &loaded.warnings {
+        eprintln!("warning: {warning}");
+    }
+    for (lang, sheets) in loaded.languages {
         for (sheet, keys) in sheets {
             let dir = out.join(&lang);
             std::fs::create_dir_all(&dir)?;
@@ -21,5 +50,10 @@ fn main() -> Result<()> {
         }
     }
 
+    std::fs::write(
+        out.join("origins.json"),
+        serde_json::to_string_pretty(&loaded.origins)?,
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,91 @@
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use rabex_env::handle::ScriptFilterContains;
+use rayon::prelude::*;
+use silksong_data_dump::acquisition::AcquisitionIndex;
+use silksong_data_dump::addressables;
+use silksong_data_dump::generated::{CollectableItemRelicType, Quest, ShopItem};
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::rooms::RoomGraph;
+use silksong_data_dump::scenes::Scene;
+
+fn main() -> Result<()> {
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+
+    let out = Path::new("out");
+    let scenes_dir = out.join("scenes");
+    let _ = std::fs::remove_dir_all(&scenes_dir);
+    std::fs::create_dir_all(&scenes_dir)?;
+    // the scenes and the files next to them are one dump, described by `out/metadata.json`
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+
+    let start = Instant::now();
+    let scenes = addressables::bundles_in(&env, Path::new(addressables::SCENES_DIR))?
+        .par_iter()
+        .map(|bundle| {
+            let name = bundle.file_stem().unwrap().to_string_lossy();
+            let file = env.load_addressables_bundle_content(bundle)?;
+            let scene =
+                Scene::read(&name, file).with_context(|| format!("Reading scene {name}"))?;
+
+            std::fs::write(
+                scenes_dir.join(&*name).with_extension("json"),
+                serde_json::to_string_pretty(&scene)?,
+            )?;
+            Ok(scene)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    eprintln!(
+        "read {} scenes in {:.2}s",
+        scenes.len(),
+        start.elapsed().as_secs_f32()
+    );
+
+    let mut rooms = RoomGraph::default();
+    let mut acquisition = AcquisitionIndex::default();
+    let mut warnings = Vec::new();
+    for scene in &scenes {
+        rooms.add_scene(scene);
+        acquisition.add_scene(scene, &mut warnings);
+    }
+
+    std::fs::write(
+        out.join("rooms.json"),
+        serde_json::to_string_pretty(&rooms)?,
+    )?;
+    std::fs::write(out.join("rooms.dot"), rooms.to_dot())?;
+
+    let data_assets = Path::new(addressables::DATA_ASSETS_DIR);
+
+    let file =
+        env.load_addressables_bundle_content(data_assets.join("questsystem/quests.bundle"))?;
+    for quest in file.scripts::<Quest>(&"Quest")? {
+        acquisition.add_quest(file.reborrow(), &quest.read()?)?;
+    }
+    let file = env.load_addressables_bundle_content(data_assets.join("shopitems.bundle"))?;
+    acquisition.add_shop_lists(file.reborrow(), &mut warnings)?;
+    for shop_item in file.scripts::<ShopItem>(&"ShopItem")? {
+        acquisition.add_shop_item(file.reborrow(), &shop_item.read()?)?;
+    }
+    let file = env.load_addressables_bundle_content(
+        data_assets.join("collectables/collectableitems.bundle"),
+    )?;
+    for relic_type in
+        file.scripts::<CollectableItemRelicType>(&ScriptFilterContains("CollectableItemRelicType"))?
+    {
+        acquisition.add_relic_type(&relic_type.read()?, &mut warnings);
+    }
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    std::fs::write(
+        out.join("acquisition.json"),
+        serde_json::to_string_pretty(&acquisition)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use rabex_env::handle::{ScriptFilter, ScriptFilterContains};
+use serde_json::Value;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::{cache, typetree};
+
+/// Usage: `dump_scripts <bundle> <script> [--contains] [--text <LANG>]`
+///
+/// Dumps every MonoBehaviour of `bundle` with the given script to `out/scripts/<script>.json`,
+/// read through its type tree. `PPtr`s are rendered as the names of the objects they point to,
+/// with `--text` `LocalisedString`s are replaced with their text.
+fn main() -> Result<()> {
+    let args = Args::from_env(&["--contains"], &["--text"])?;
+    let contains = args.flag("--contains");
+    let text_lang = args.option("--text");
+    let [bundle, script] = args.positional() else {
+        bail!("expected <bundle> <script>");
+    };
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let mut warnings = Vec::new();
+    let languages = match text_lang {
+        Some(_) => cache::languages(&env, &mut warnings)?,
+        None => Default::default(),
+    };
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    let lang = text_lang
+        .map(|lang| {
+            languages
+                .get(lang)
+                .with_context(|| format!("unknown language '{lang}'"))
+        })
+        .transpose()?;
+
+    let file = env.load_addressables_bundle_content(bundle)?;
+    let filter: &dyn ScriptFilter = match contains {
+        true => &ScriptFilterContains(script),
+        false => &script.as_str(),
+    };
+
+    let mut names = HashMap::new();
+    let mut objects = Vec::new();
+    for object in file.scripts::<Value>(filter)? {
+        let mut value = object.read()?;
+        typetree::resolve_pptr_names(file.reborrow(), &mut value, &mut names);
+        if let Some(lang) = lang {
+            typetree::resolve_localised(&mut value, lang);
+        }
+        objects.push(value);
+    }
+    if objects.is_empty() {
+        eprintln!("warning: no objects with script '{script}' in {bundle}");
+    }
+
+    let out = Path::new("out/scripts");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    std::fs::write(
+        out.join(script).with_extension("json"),
+        serde_json::to_string_pretty(&objects)?,
+    )?;
+
+    Ok(())
+}
@@ -1,6 +1,4 @@
-#![allow(non_snake_case)]
-use std::fmt::{Debug, Write};
-use std::fs::File;
+use std::fmt::Debug;
 use std::path::Path;
 
 use anyhow::{Context, Result};
@@ -8,244 +6,330 @@ use rabex_env::Environment;
 use rabex_env::handle::{ScriptFilter, ScriptFilterContains, SerializedFileHandle};
 use serde::{Deserialize, Serialize};
 
-use silksong_data_dump::enums::*;
+use silksong_data_dump::addressables;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::cache::{self, Cache};
+use silksong_data_dump::damage::{self, DamageModel, ToolDamageTags};
 use silksong_data_dump::generated::*;
-use silksong_data_dump::lang;
+use silksong_data_dump::lang::Language;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::parallel::{self, Task};
+use silksong_data_dump::playerdata::PlayerDataIndex;
+use silksong_data_dump::references::ReferenceIndex;
+use silksong_data_dump::tables::*;
+use silksong_data_dump::warning::Warning;
 
+/// Usage: `dump_silksong_data [--version-column]`
+///
+/// `--version-column` appends a `gameVersion` column to every CSV table.
 fn main() -> Result<()> {
-    let env = silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let version_column = Args::from_env(&["--version-column"], &[])?.flag("--version-column");
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
 
     let out = Path::new("out");
     std::fs::create_dir_all(out)?;
+    let metadata = GameMetadata::read(&env, Some(steam_app))?;
+    metadata.write(out)?;
+    let version = version_column.then(|| metadata.version_string());
+    let version = version.as_deref();
 
-    let languages = lang::get_language_keys(&env)?;
-    let lang = &languages["en"];
+    let mut warnings = Vec::new();
+    let languages = cache::languages(&env, &mut warnings)?;
+    report_warnings(&warnings);
+    let lang = languages
+        .get("en")
+        .context("No english language assets found")?;
 
-    let data_assets = Path::new("dataassets_assets_assets/dataassets");
+    let data_assets = Path::new(addressables::DATA_ASSETS_DIR);
+    let cache = &Cache::open(&env)?;
+    let env = &env;
 
-    dump_csv::<IntReference, IntReferenceData>(&env, out, data_assets, "costs", &"CostReference")?;
-    dump_csv::<IntReference, IntReferenceData>(
-        &env,
-        out,
-        data_assets,
-        "damages",
-        &"DamageReference",
-    )?;
-    dump_csv_with::<CollectableItemRelicType, _>(
-        &env,
-        out,
-        data_assets,
-        "collectables/collectableitems",
-        &ScriptFilterContains("CollectableItemRelicType"),
-        |_, item| {
-            Ok(CollectableItemData {
-                name: item.m_Name,
-                displayName: item.typeName.get(lang).to_owned(),
-                rewardAmount: item.rewardAmount,
-            })
-        },
-    )?;
-    dump_csv_with::<EnemyJournalRecord, _>(
-        &env,
-        out,
-        data_assets,
-        "enemyjournal/journalrecords",
-        &"EnemyJournalRecord",
-        |_, item| {
-            Ok(EnemyJournalRecordData {
-                name: item.displayName.get(lang).to_owned(),
-                killsRequired: item.killsRequired,
-                recordType: item.recordType.try_into().unwrap(),
-                requiredType: item.requiredType.try_into().unwrap(),
-            })
-        },
-    )?;
-    dump_csv_with::<ToolItemBasic, _>(
-        &env,
-        out,
-        data_assets,
-        "tools/toolitems",
-        &ScriptFilterContains("Tool"),
-        |file, item| {
-            let display_name = file
-                .deref_read_optional(item.countKey)?
-                .and_then(|x| x.displayName)
-                .map(|name| name.get(lang).to_owned())
-                .filter(|name| *name != "Ruined Tool");
-            assert!(matches!(
-                item.replenishUsage.try_into().unwrap(),
-                ReplenishUsage::Percentage
-            ));
-            assert_eq!(item.replenishUsageMultiplier, 1.0);
-            let replenishCost = if item.baseStorageAmount != 0
-                && !matches!(
-                    item.replenishResource.try_into().unwrap(),
-                    ReplenishResources::None
-                ) {
-                format!(
-                    "1/{} * 40 = {:.1}",
-                    item.baseStorageAmount,
-                    1.0 / item.baseStorageAmount as f32 * 40.,
+    let tasks: Vec<Task> = vec![
+        (
+            "costs",
+            Box::new(|| {
+                dump_csv::<IntReference, IntReferenceData>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "costs",
+                    &"CostReference",
+                )
+            }),
+        ),
+        (
+            "damages",
+            Box::new(|| {
+                dump_csv::<IntReference, IntReferenceData>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "damages",
+                    &"DamageReference",
+                )
+            }),
+        ),
+        (
+            "references",
+            Box::new(|| dump_references(env, cache, out, data_assets)),
+        ),
+        (
+            "localised",
+            Box::new(|| dump_localised(env, cache, out, lang)),
+        ),
+        (
+            "playerdata",
+            Box::new(|| dump_player_data(env, out, version, data_assets)),
+        ),
+        (
+            "collectableitems",
+            Box::new(|| {
+                dump_csv_with::<CollectableItemRelicType, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "collectables/collectableitems",
+                    &ScriptFilterContains("CollectableItemRelicType"),
+                    |_, item| CollectableItemData::new(item, lang),
+                )
+            }),
+        ),
+        (
+            "journalrecords",
+            Box::new(|| {
+                dump_csv_with::<EnemyJournalRecord, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "enemyjournal/journalrecords",
+                    &"EnemyJournalRecord",
+                    |_, item| EnemyJournalRecordData::new(item, lang),
+                )
+            }),
+        ),
+        (
+            "toolitems",
+            Box::new(|| {
+                let mut warnings = Vec::new();
+                dump_csv_with::<ToolItemBasic, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "tools/toolitems",
+                    &ScriptFilterContains("Tool"),
+                    |file, item| {
+                        let count_key = file.deref_read_optional(item.countKey)?;
+                        let display_name = tool_display_name(count_key, lang);
+                        ToolItemData::new(item, display_name, &mut warnings)
+                    },
+                )?;
+                report_warnings(&warnings);
+                Ok(())
+            }),
+        ),
+        (
+            "quests",
+            Box::new(|| {
+                dump_csv_with::<Quest, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "questsystem/quests",
+                    &"Quest",
+                    |file, item| {
+                        let reward = file.deref_read_optional(item.rewardItem)?;
+                        let counters = item
+                            .targets
+                            .iter()
+                            .map(|x| {
+                                Ok(file
+                                    .deref_read_optional(x.Counter)?
+                                    .map(|x| x.m_Name)
+                                    .unwrap_or_default())
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        Ok(QuestData::new(item, lang, reward, &counters))
+                    },
+                )
+            }),
+        ),
+        (
+            "damagetags",
+            Box::new(|| {
+                dump_csv_with::<DamageTag, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "damagetags",
+                    &"DamageTag",
+                    |file, item| {
+                        let damage_cooldown = file.deref_read_optional(item.damageCooldownTimer)?;
+                        DamageTagData::new(item, damage_cooldown)
+                    },
                 )
-            } else {
-                "".to_string()
-            };
-            Ok(ToolItemData {
-                name: display_name.unwrap_or(item.m_Name),
-                r#type: item.r#type.try_into().unwrap(),
-                damageFlags: item.damageFlags.try_into().unwrap(),
-                poisonDamageTicks: item.poisonDamageTicks,
-                SilkRequired: item.usageOptions.SilkRequired,
-                replenishCost,
-            })
-        },
+            }),
+        ),
+        (
+            "damagecomparison",
+            Box::new(|| dump_damage_comparison(env, out, version, data_assets, lang)),
+        ),
+        (
+            "shopitems",
+            Box::new(|| {
+                dump_csv_with::<ShopItem, _>(
+                    env,
+                    out,
+                    version,
+                    data_assets,
+                    "shopitems",
+                    &"ShopItem",
+                    |file, item| {
+                        let cost_ref = file.deref_read_optional(item.costReference)?;
+                        let required_item = file.deref_read_optional(item.requiredItem)?;
+                        let quests = item
+                            .questsAppearConditions
+                            .iter()
+                            .map(|quest_test| Ok(file.deref(quest_test.Quest)?.read()?.m_Name))
+                            .collect::<Result<Vec<_>>>()?;
+                        ShopItemData::new(item, lang, cost_ref, required_item, &quests)
+                    },
+                )
+            }),
+        ),
+    ];
+    parallel::run_timed(&tasks)
+}
+
+fn dump_references(env: &Environment, cache: &Cache, out: &Path, data_assets: &Path) -> Result<()> {
+    let mut index = ReferenceIndex::default();
+    let costs = cache.bundle(env, &data_assets.join("costs.bundle"))?;
+    index.add_references(&costs, "CostReference")?;
+    let damages = cache.bundle(env, &data_assets.join("damages.bundle"))?;
+    index.add_references(&damages, "DamageReference")?;
+
+    // enemies are placed in scenes, so they're scanned for their damage references too
+    let mut bundles = addressables::bundles_in(env, data_assets)?;
+    bundles.extend(addressables::bundles_in(
+        env,
+        Path::new(addressables::SCENES_DIR),
+    )?);
+    let partials = cache.scan_bundles(env, data_assets, &bundles, |name, bundle| {
+        let mut partial = index.without_usages();
+        partial.add_usages(name, bundle);
+        partial
+    })?;
+    for partial in partials {
+        index.merge(partial);
+    }
+
+    std::fs::write(
+        out.join("references.json"),
+        serde_json::to_string_pretty(&index)?,
     )?;
-    dump_csv_with::<Quest, _>(
-        &env,
-        out,
-        data_assets,
-        "questsystem/quests",
-        &"Quest",
-        |file, item| {
-            let name = item
-                .invItemAppendDesc
-                .try_get(lang)
-                .map(ToOwned::to_owned)
-                .unwrap_or(item.m_Name);
-            let reward = file
-                .deref_read_optional(item.rewardItem)?
-                .map(|x| x.m_Name)
-                .unwrap_or_default();
+    Ok(())
+}
+
+fn dump_localised(env: &Environment, cache: &Cache, out: &Path, lang: &Language) -> Result<()> {
+    let index = cache::localised_index(env, cache)?;
+
+    for (sheet, key) in index.unresolved(lang) {
+        eprintln!("warning: unresolved localised string {sheet}/{key}");
+    }
 
-            Ok(QuestData {
-                name,
-                // getTargetCondition: item.getTargetCondition.to_string(),
-                condition: item.playerDataTest.to_string(),
-                rewardCount: item.rewardCount,
-                rewardItem: reward,
-                rewardCountAct3: item.rewardCountAct3,
-                // targetCount: item.targetCount,
-                requirements: item
-                    .targets
-                    .iter()
-                    // .map(|x| format!("{} {},", x.AltTest.to_string(), x.Count))
-                    .map(|x| {
-                        let item = file
-                            .deref_read_optional(x.Counter)
-                            .unwrap()
-                            .map(|x| x.m_Name)
-                            .unwrap_or_default();
-                        if x.AltTest.TestGroups.is_empty() {
-                            format!("{} {} ", x.Count, item)
-                        } else {
-                            format!("{} ", x.AltTest)
-                        }
-                    })
-                    .collect(),
-            })
-        },
+    std::fs::write(
+        out.join("localised.json"),
+        serde_json::to_string_pretty(&index)?,
     )?;
-    dump_csv_with::<DamageTag, _>(
-        &env,
-        out,
-        data_assets,
-        "damagetags",
-        &"DamageTag",
-        |file, item| {
-            let damage_cooldown = file.deref_read_optional(item.damageCooldownTimer)?;
-            Ok(DamageTagData {
-                name: item.m_Name,
-                damageAmount: item.damageAmount,
-                damageCooldownTimer: damage_cooldown.map(|cooldown| format!("{}s", cooldown.delay)),
-                delayPerHit: item.delayPerHit,
-                isToolDamage: item.isToolDamage,
-                nailElement: item.nailElement.try_into().unwrap(),
-                specialDamageType: item.specialDamageType.try_into().unwrap(),
-                startDelay: item.startDelay,
-                totalHitLimit: item.totalHitLimit,
-            })
-        },
+    std::fs::write(
+        out.join("localised_orphans.json"),
+        serde_json::to_string_pretty(&index.orphans(lang))?,
     )?;
-    dump_csv_with::<ShopItem, _>(
-        &env,
-        out,
-        data_assets,
-        "shopitems",
-        &"ShopItem",
-        |file, item| {
-            let cost_ref = file.deref_read_optional(item.costReference)?;
-            let required_item = file.deref_read_optional(item.requiredItem)?;
-            let conditions = if !item.extraAppearConditions.TestGroups.is_empty() {
-                Some(item.extraAppearConditions.to_string())
-            } else {
-                None
-            };
+    Ok(())
+}
 
-            let quest_requirement = if !item.questsAppearConditions.is_empty() {
-                let mut quests_str = String::new();
-                for quest_test in &item.questsAppearConditions {
-                    let quest = file.deref(quest_test.Quest)?.read()?;
-                    write!(&mut quests_str, "'{}'", quest.m_Name)?;
-                    let m = |b: u8| match b != 0 {
-                        true => "",
-                        false => "not ",
-                    };
-                    if quest_test.CheckAvailable != 0 {
-                        write!(&mut quests_str, " {}available", m(quest_test.IsAvailable),)?;
-                    }
-                    if quest_test.CheckAccepted != 0 {
-                        write!(&mut quests_str, " {}accepted", m(quest_test.IsAccepted),)?;
-                    }
-                    if quest_test.CheckCompletedAmount != 0 {
-                        write!(
-                            &mut quests_str,
-                            " completedamount {}",
-                            quest_test.CompletedAmount
-                        )?;
-                    }
-                    if quest_test.CheckCompletable != 0 {
-                        write!(
-                            &mut quests_str,
-                            " {}completable",
-                            m(quest_test.IsCompletable),
-                        )?;
-                    }
-                    if quest_test.CheckCompleted != 0 {
-                        write!(&mut quests_str, " {}completed", m(quest_test.IsCompleted),)?;
-                    }
-                    if quest_test.CheckWasEverCompleted != 0 {
-                        write!(
-                            &mut quests_str,
-                            " {}completed",
-                            m(quest_test.WasEverCompleted),
-                        )?;
-                    }
-                }
-                Some(quests_str)
-            } else {
-                None
-            };
+fn dump_player_data(
+    env: &Environment,
+    out: &Path,
+    version: Option<&str>,
+    data_assets: &Path,
+) -> Result<()> {
+    let mut index = PlayerDataIndex::default();
 
-            let display_name = item.displayName.get(lang).to_owned();
-            let internal_name = item.m_Name;
+    let file =
+        env.load_addressables_bundle_content(data_assets.join("questsystem/quests.bundle"))?;
+    for quest in file.scripts::<Quest>(&"Quest")? {
+        index.add_quest(&quest.read()?);
+    }
+    let file = env.load_addressables_bundle_content(data_assets.join("shopitems.bundle"))?;
+    for item in file.scripts::<ShopItem>(&"ShopItem")? {
+        index.add_shop_item(&item.read()?);
+    }
+    let file = env.load_addressables_bundle_content(data_assets.join("tools/toolitems.bundle"))?;
+    for tool in file.scripts::<ToolItemBasic>(&ScriptFilterContains("Tool"))? {
+        index.add_tool(&tool.read()?);
+    }
 
-            Ok(ShopItemData {
-                name: display_name,
-                internalName: internal_name,
-                cost: cost_ref.map(|cost| cost.value).unwrap_or(item.cost),
-                item: required_item.map(|item| item.m_Name),
-                conditions,
-                quest: quest_requirement,
-            })
-        },
-    )?;
+    let mut writer = TableWriter::create(&out.join("playerdata.csv"), version)?;
+    for row in index.table() {
+        writer.serialize(row)?;
+    }
+    writer.into_inner()?;
+    Ok(())
+}
+
+fn report_warnings(warnings: &[Warning]) {
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+}
 
+fn dump_damage_comparison(
+    env: &Environment,
+    out: &Path,
+    version: Option<&str>,
+    data_assets: &Path,
+    lang: &Language,
+) -> Result<()> {
+    let file = env.load_addressables_bundle_content(data_assets.join("damagetags.bundle"))?;
+    let mut tags = Vec::new();
+    for tag in file.scripts::<DamageTag>(&"DamageTag")? {
+        let tag = tag.read()?;
+        let cooldown = file.deref_read_optional(tag.damageCooldownTimer)?;
+        tags.push(DamageModel::new(&tag, cooldown.as_ref())?);
+    }
+
+    let file = env.load_addressables_bundle_content(data_assets.join("tools/toolitems.bundle"))?;
+    let mut tools = Vec::new();
+    for tool in file.scripts::<ToolItemBasic>(&ScriptFilterContains("Tool"))? {
+        let tool = tool.read()?;
+        let count_key = file.deref_read_optional(tool.countKey)?;
+        let name = tool_display_name(count_key, lang).unwrap_or(tool.m_Name.clone());
+        tools.push((name, tool));
+    }
+
+    let mut writer = TableWriter::create(&out.join("damagecomparison.csv"), version)?;
+    let mut warnings = Vec::new();
+    let tool_tags = ToolDamageTags::find(&tags, &mut warnings);
+    report_warnings(&warnings);
+    for row in damage::compare(&tags, tool_tags, &tools) {
+        writer.serialize(row)?;
+    }
+    writer.into_inner()?;
     Ok(())
 }
 
 fn dump_csv<T, U>(
     env: &Environment,
     out: &Path,
+    version: Option<&str>,
     data_assets: &Path,
     name: &str,
     script: &dyn ScriptFilter,
@@ -254,14 +338,16 @@ where
     T: for<'de> Deserialize<'de>,
     U: From<T> + Serialize + Debug,
 {
-    dump_csv_with::<T, U>(env, out, data_assets, name, script, |_, val| {
+    dump_csv_with::<T, U>(env, out, version, data_assets, name, script, |_, val| {
         Ok(U::from(val))
     })
 }
 
+/// Writes `out/<name>.csv`, with a `gameVersion` column if `version` is given.
 fn dump_csv_with<T, U>(
     env: &Environment,
     out: &Path,
+    version: Option<&str>,
     data_assets: &Path,
     name: &str,
     script: &dyn ScriptFilter,
@@ -274,9 +360,8 @@ where
     let name = Path::new(name);
     let path = data_assets.join(name).with_extension("bundle");
     let file = env.load_addressables_bundle_content(path)?;
-    let mut writer = csv::Writer::from_writer(File::create(
-        out.join(name.file_name().unwrap()).with_extension("csv"),
-    )?);
+    let path = out.join(name.file_name().unwrap()).with_extension("csv");
+    let mut writer = TableWriter::create(&path, version)?;
     for value in file.scripts::<T>(script)? {
         let value = f(
             file.reborrow(),
@@ -287,91 +372,6 @@ where
 
         writer.serialize(value)?;
     }
+    writer.into_inner()?;
     Ok(())
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-struct EnemyJournalRecordData {
-    name: String,
-    killsRequired: i32,
-    recordType: RecordTypes,
-    requiredType: RequiredTypes,
-}
-
-#[derive(Debug, Serialize)]
-struct CollectableItemData {
-    name: String,
-    displayName: String,
-    rewardAmount: i32,
-}
-#[derive(Debug, Serialize)]
-struct ShopItemData {
-    name: String,
-    internalName: String,
-    cost: i32,
-    item: Option<String>,
-    conditions: Option<String>,
-    quest: Option<String>,
-}
-#[derive(Debug, Serialize)]
-struct DamageTagData {
-    name: String,
-    damageAmount: i32,
-    damageCooldownTimer: Option<String>,
-    delayPerHit: f32,
-    isToolDamage: u8,
-    nailElement: NailElement,
-    specialDamageType: SpecialDamageType,
-    startDelay: f32,
-    totalHitLimit: i32,
-}
-
-#[derive(Debug, Serialize)]
-struct ToolItemData {
-    r#type: ToolItemType,
-    name: String,
-    damageFlags: ToolDamageFlags,
-    poisonDamageTicks: i32,
-    // replenishResource: ReplenishResources,
-    replenishCost: String,
-    #[serde(serialize_with = "serialize_num_bool")]
-    SilkRequired: i32,
-}
-
-#[derive(Debug, Serialize)]
-struct QuestData {
-    name: String,
-    // getTargetCondition: String, empty
-    // persistentBoolTests: Vec<serde_json::Value>,
-    // questType: PPtr,
-    // require stuff
-    rewardItem: String,
-    rewardCount: i32,
-    rewardCountAct3: i32,
-    // targetCount: i32,
-    requirements: String,
-    condition: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct IntReferenceData {
-    pub name: String,
-    pub value: i32,
-}
-impl From<IntReference> for IntReferenceData {
-    fn from(value: IntReference) -> Self {
-        IntReferenceData {
-            name: value.m_Name,
-            value: value.value,
-        }
-    }
-}
-
-#[allow(dead_code)]
-fn serialize_num_bool<S: serde::Serializer>(val: &i32, s: S) -> Result<S::Ok, S::Error> {
-    s.serialize_str(match val {
-        0 => "",
-        1 => "yes",
-        _ => unreachable!(),
-    })
-}
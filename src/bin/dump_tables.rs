@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use silksong_data_dump::args::Args;
+use silksong_data_dump::cache;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::parallel::{self, Task};
+use silksong_data_dump::table_spec::TableSpecs;
+
+/// Usage: `dump_tables [tables.json] [--version-column]`
+///
+/// Writes one CSV per table defined in the spec file to `out/`, along with `out/metadata.json`.
+/// `--version-column` appends a `gameVersion` column to every table.
+fn main() -> Result<()> {
+    let args = Args::from_env(&["--version-column"], &[])?;
+    let version_column = args.flag("--version-column");
+    let spec_path = args
+        .positional()
+        .first()
+        .map_or("tables.json", String::as_str);
+    let specs = TableSpecs::read(Path::new(&spec_path))?;
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let mut warnings = Vec::new();
+    let languages = cache::languages(&env, &mut warnings)?;
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    let lang = languages
+        .get("en")
+        .context("No english language assets found")?;
+
+    let out = Path::new("out");
+    std::fs::create_dir_all(out)?;
+    let metadata = GameMetadata::read(&env, Some(steam_app))?;
+    metadata.write(out)?;
+    let version = version_column.then(|| metadata.version_string());
+
+    let env = &env;
+    let version = version.as_deref();
+    let tasks: Vec<Task> = specs
+        .tables
+        .iter()
+        .map(|spec| -> Task {
+            (
+                spec.name.as_str(),
+                Box::new(move || {
+                    for warning in spec.dump(env, out, lang, version)? {
+                        eprintln!("warning: {warning}");
+                    }
+                    Ok(())
+                }),
+            )
+        })
+        .collect();
+    parallel::run_timed(&tasks)
+}
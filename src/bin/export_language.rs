@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use silksong_data_dump::args::Args;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::xliff::{self, XliffVersion};
+use silksong_data_dump::{cache, translation};
+
+/// Usage: `export_language <source-lang> <target-lang> [--format po|xliff1.2|xliff2.0]`
+///
+/// Writes `out/translations/<source>-<target>.{po,xlf}`.
+fn main() -> Result<()> {
+    let args = Args::from_env(&[], &["--format"])?;
+    let format = args.option("--format").unwrap_or("po");
+    let [source_lang, target_lang] = args.positional() else {
+        bail!("expected <source-lang> <target-lang>");
+    };
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let mut warnings = Vec::new();
+    let languages = cache::languages(&env, &mut warnings)?;
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    let source = languages
+        .get(source_lang)
+        .with_context(|| format!("unknown language '{source_lang}'"))?;
+    let target = languages
+        .get(target_lang)
+        .with_context(|| format!("unknown language '{target_lang}'"))?;
+
+    let (contents, extension) = match format {
+        "po" => (
+            translation::to_po(target_lang, source, target).write(),
+            "po",
+        ),
+        "xliff1.2" => (
+            xliff::write(
+                XliffVersion::V1_2,
+                (source_lang, source),
+                (target_lang, target),
+            ),
+            "xlf",
+        ),
+        "xliff2.0" => (
+            xliff::write(
+                XliffVersion::V2_0,
+                (source_lang, source),
+                (target_lang, target),
+            ),
+            "xlf",
+        ),
+        other => bail!("unknown format '{other}'"),
+    };
+
+    let out = Path::new("out/translations");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    std::fs::write(
+        out.join(format!("{source_lang}-{target_lang}.{extension}")),
+        contents,
+    )?;
+
+    Ok(())
+}
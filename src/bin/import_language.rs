@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use silksong_data_dump::args::Args;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::translation::{self, IssueKind};
+use silksong_data_dump::{cache, lang};
+
+/// Usage: `import_language <out/languages | translation.csv | translation.po> [--lang <LANG>] [--force]`
+///
+/// Writes the patched, encrypted `TextAsset` payloads to `out/language_assets/<LANG>_<sheet>.txt`.
+fn main() -> Result<()> {
+    let args = Args::from_env(&["--force"], &["--lang"])?;
+    let force = args.flag("--force");
+    let lang_arg = args.option("--lang");
+    let input = args
+        .positional()
+        .first()
+        .context("missing path of translations to import")?;
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let mut warnings = Vec::new();
+    let originals = cache::languages(&env, &mut warnings)?;
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let mut imported = translation::read(Path::new(input), lang_arg)?;
+    if let Some(lang) = lang_arg {
+        imported.retain(|imported_lang, _| imported_lang == lang);
+    }
+
+    // every language is checked before anything is written, so a failed import leaves no partial output
+    let mut mismatches = 0;
+    let mut assets = Vec::new();
+    for (lang, language) in &imported {
+        let original = originals
+            .get(lang)
+            .with_context(|| format!("unknown language '{lang}'"))?;
+
+        let issues = translation::validate(original, language);
+        let missing = issues
+            .iter()
+            .filter(|issue| issue.kind == IssueKind::Missing)
+            .count();
+        for issue in issues
+            .iter()
+            .filter(|issue| issue.kind != IssueKind::Missing)
+        {
+            let detail = issue.detail.as_deref().unwrap_or_default();
+            eprintln!(
+                "warning: {lang} {}/{}: {:?} {detail}",
+                issue.sheet, issue.key, issue.kind
+            );
+            if issue.kind == IssueKind::PlaceholderMismatch {
+                mismatches += 1;
+            }
+        }
+        if missing > 0 {
+            eprintln!("{lang}: {missing} keys not translated, keeping the original values");
+        }
+
+        let patched = translation::patch(original, language);
+        assets.extend(lang::write_language_assets(lang, &patched)?);
+    }
+
+    if mismatches > 0 && !force {
+        bail!("{mismatches} placeholder mismatches, fix them or pass --force");
+    }
+
+    let out = Path::new("out/language_assets");
+    let _ = std::fs::remove_dir_all(out);
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    for (name, payload) in assets {
+        std::fs::write(out.join(name).with_extension("txt"), payload)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+use silksong_data_dump::args::Args;
+use silksong_data_dump::cache::{self, Cache};
+use silksong_data_dump::localised::LocalisedIndex;
+use silksong_data_dump::search::{self, SearchOptions};
+
+/// Usage: `search_text <query> [--lang <LANG>] [--fuzzy] [--no-usages]`
+///
+/// Prints every matching `sheet/key`, followed by the data assets referencing it.
+fn main() -> Result<()> {
+    let args = Args::from_env(&["--fuzzy", "--no-usages"], &["--lang"])?;
+    let query = args.positional().join(" ");
+    anyhow::ensure!(!query.is_empty(), "missing search query");
+
+    let options = SearchOptions {
+        lang: args.option("--lang").map(ToOwned::to_owned),
+        fuzzy: args.flag("--fuzzy"),
+    };
+    let with_usages = !args.flag("--no-usages");
+
+    let (env, _) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let cache = Cache::open(&env)?;
+    let mut warnings = Vec::new();
+    let languages = cache::languages(&env, &mut warnings)?;
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    let matches = search::search(&languages, &query, &options);
+
+    let index = match with_usages && !matches.is_empty() {
+        true => cache::localised_index(&env, &cache)?,
+        false => LocalisedIndex::default(),
+    };
+
+    for m in &matches {
+        println!("{} {}/{}: {}", m.lang, m.sheet, m.key, m.value);
+        for usage in index.usages(&m.sheet, &m.key) {
+            println!(
+                "    {} {} '{}' {}",
+                usage.bundle, usage.script, usage.name, usage.field
+            );
+        }
+    }
+    if matches.is_empty() {
+        eprintln!("no matches for '{query}'");
+    }
+
+    Ok(())
+}
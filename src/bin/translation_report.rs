@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use silksong_data_dump::args::Args;
+use silksong_data_dump::metadata::GameMetadata;
+use silksong_data_dump::{cache, translation};
+
+/// Usage: `translation_report [--reference <LANG>]`
+///
+/// Writes `out/translation_report.json` and `out/translation_report.md`.
+fn main() -> Result<()> {
+    let args = Args::from_env(&[], &["--reference"])?;
+    let reference_lang = args.option("--reference").unwrap_or("en");
+
+    let (env, steam_app) =
+        silksong_data_dump::detect_game()?.context("Couldn't find silksong game files")?;
+    let mut warnings = Vec::new();
+    let languages = cache::languages(&env, &mut warnings)?;
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    let reference = languages
+        .get(reference_lang)
+        .with_context(|| format!("unknown reference language '{reference_lang}'"))?;
+
+    let report: IndexMap<_, _> = languages
+        .iter()
+        .filter(|(lang, _)| *lang != reference_lang)
+        .map(|(lang, language)| (lang.clone(), translation::coverage(reference, language)))
+        .collect();
+
+    let out = Path::new("out");
+    std::fs::create_dir_all(out)?;
+    GameMetadata::read(&env, Some(steam_app))?.write(out)?;
+    std::fs::write(
+        out.join("translation_report.json"),
+        serde_json::to_string_pretty(&report)?,
+    )?;
+    std::fs::write(
+        out.join("translation_report.md"),
+        translation::coverage_markdown(&report),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+//! On-disk cache of decoded assets, so repeated runs don't have to parse the game files again.
+//!
+//! Entries are stored as JSON in `cache/<fingerprint>/`, where the fingerprint is derived from the size and
+//! modification time of the game files and the version of this tool. When either changes, the fingerprint changes
+//! and old entries are removed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use rabex_env::Environment;
+use rabex_env::handle::ScriptFilterContains;
+use rabex_env::rabex::objects::PPtr;
+use rayon::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::addressables;
+use crate::lang::{self, Languages};
+use crate::localised::LocalisedIndex;
+use crate::references::{ReferenceTarget, reference_target, script_name};
+use crate::typetree;
+use crate::warning::Warning;
+
+/// Overrides the cache directory.
+pub const CACHE_DIR_VAR: &str = "SILKSONG_CACHE_DIR";
+const DEFAULT_CACHE_DIR: &str = "cache";
+/// Marks the directories created by this cache, so only those are ever removed.
+const MARKER: &str = ".silksong-data-dump-cache";
+
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn open(env: &Environment) -> Result<Cache> {
+        let root = std::env::var_os(CACHE_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_DIR));
+        Cache::open_at(&root, &fingerprint(env)?)
+    }
+
+    /// Opens the cache for `fingerprint` in `root`, removing the caches of all other fingerprints.
+    ///
+    /// Only directories with the marker file are removed, anything else in `root` is kept.
+    pub fn open_at(root: &Path, fingerprint: &str) -> Result<Cache> {
+        std::fs::create_dir_all(root)
+            .with_context(|| format!("failed to create cache directory {}", root.display()))?;
+        for entry in std::fs::read_dir(root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() || entry.file_name() == fingerprint {
+                continue;
+            }
+            if entry.path().join(MARKER).is_file() {
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+
+        let dir = root.join(fingerprint);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(MARKER), "")?;
+        Ok(Cache { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached entry `name`, or computes and stores it.
+    ///
+    /// Entries which fail to deserialize, e.g. after their type changed, are recomputed.
+    pub fn get_or_insert_with<T>(&self, name: &str, f: impl FnOnce() -> Result<T>) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let path = self.dir.join(name).with_extension("json");
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if let Ok(data) = std::fs::read(&path) {
+            match serde_json::from_slice(&data) {
+                Ok(value) => return Ok(value),
+                Err(e) => eprintln!("warning: ignoring invalid cache entry {name}: {e}"),
+            }
+        }
+
+        let value = f()?;
+        // written to a temporary file first, so concurrent runs never see partial entries
+        let tmp = path.with_extension(format!("json.{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec(&value)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(value)
+    }
+
+    /// The MonoBehaviours of `bundle`, read through their type trees.
+    pub fn bundle(&self, env: &Environment, bundle: &Path) -> Result<CachedBundle> {
+        let name = Path::new("bundles").join(bundle);
+        self.get_or_insert_with(&name.to_string_lossy(), || {
+            let file = env.load_addressables_bundle_content(bundle)?;
+            let mut script_names = HashMap::new();
+            let mut targets = HashMap::new();
+            let mut objects = Vec::new();
+            for object in file.scripts::<Value>(&ScriptFilterContains(""))? {
+                let path_id = object.path_id();
+                let value = object.read()?;
+                let script = script_name(file.reborrow(), &value, &mut script_names);
+                for (_, pptr) in typetree::object_pptrs(&value) {
+                    targets
+                        .entry((pptr.m_FileID, pptr.m_PathID))
+                        .or_insert_with(|| reference_target(file.reborrow(), pptr));
+                }
+                objects.push(CachedObject {
+                    path_id,
+                    script,
+                    value,
+                });
+            }
+            let targets = targets
+                .into_iter()
+                .filter_map(|((file_id, _), target)| Some((file_id, target?)))
+                .collect();
+            Ok(CachedBundle { objects, targets })
+        })
+    }
+
+    /// Reads all `bundles` in parallel and maps them with `f`, together with their path relative to `base`.
+    pub fn scan_bundles<T: Send>(
+        &self,
+        env: &Environment,
+        base: &Path,
+        bundles: &[PathBuf],
+        f: impl Fn(&str, &CachedBundle) -> T + Sync,
+    ) -> Result<Vec<T>> {
+        bundles
+            .par_iter()
+            .map(|bundle| {
+                let name = bundle.strip_prefix(base).unwrap_or(bundle);
+                let name = name.display().to_string();
+                let cached = self
+                    .bundle(env, bundle)
+                    .with_context(|| format!("Scanning {name}"))?;
+                Ok(f(&name, &cached))
+            })
+            .collect()
+    }
+}
+
+/// The MonoBehaviours of a bundle, together with what their pointers point to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedBundle {
+    pub objects: Vec<CachedObject>,
+    /// The objects shaped like an `IntReference` the pointers of `objects` point to, with the pointer's `m_FileID`.
+    pub targets: Vec<(i32, ReferenceTarget)>,
+}
+
+impl CachedBundle {
+    /// Looks up the [`ReferenceTarget`] a pointer of one of the `objects` points to.
+    pub fn target_resolver(&self) -> impl FnMut(PPtr) -> Option<ReferenceTarget> + '_ {
+        let targets: HashMap<(i32, i64), &ReferenceTarget> = self
+            .targets
+            .iter()
+            .map(|(file_id, target)| ((*file_id, target.path_id), target))
+            .collect();
+        move |pptr| {
+            targets
+                .get(&(pptr.m_FileID, pptr.m_PathID))
+                .map(|&target| target.clone())
+        }
+    }
+}
+
+/// A MonoBehaviour read as [`Value`], together with its script class name.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedObject {
+    pub path_id: i64,
+    pub script: String,
+    pub value: Value,
+}
+
+/// The languages of [`lang::get_language_keys`], cached together with the warnings of loading them.
+pub fn languages(env: &Environment, warnings: &mut Vec<Warning>) -> Result<Languages> {
+    let (languages, cached_warnings) = Cache::open(env)?.get_or_insert_with("languages", || {
+        let mut warnings = Vec::new();
+        let languages = lang::get_language_keys(env, &mut warnings)?;
+        Ok((languages, warnings))
+    })?;
+    warnings.extend(cached_warnings);
+    Ok(languages)
+}
+
+/// The [`LocalisedIndex`] of all data asset bundles, read through the cache.
+pub fn localised_index(env: &Environment, cache: &Cache) -> Result<LocalisedIndex> {
+    let data_assets = Path::new(addressables::DATA_ASSETS_DIR);
+    let bundles = addressables::bundles_in(env, data_assets)?;
+    let partials = cache.scan_bundles(env, data_assets, &bundles, |name, bundle| {
+        let mut partial = LocalisedIndex::default();
+        for object in &bundle.objects {
+            partial.add_value(name, &object.script, &object.value);
+        }
+        partial
+    })?;
+
+    let mut index = LocalisedIndex::default();
+    for partial in partials {
+        index.merge(partial);
+    }
+    Ok(index)
+}
+
+/// Fingerprint of `globalgamemanagers` and all addressable bundles.
+pub fn fingerprint(env: &Environment) -> Result<String> {
+    let game_dir = &env.game_files.game_dir;
+    let mut files = vec![PathBuf::from("globalgamemanagers")];
+    for name in ["catalog.json", "catalog.bin", "settings.json"] {
+        let path = Path::new("StreamingAssets/aa").join(name);
+        if game_dir.join(&path).exists() {
+            files.push(path);
+        }
+    }
+    if let Ok(build_dir) = addressables::build_dir(env) {
+        let build_dir = build_dir.strip_prefix(game_dir)?;
+        for bundle in addressables::bundles_in(env, Path::new(""))? {
+            files.push(build_dir.join(bundle));
+        }
+    }
+    fingerprint_files(game_dir, &files)
+}
+
+/// Hash of the version of this tool and the paths, sizes and modification times of `files` relative to `base`.
+pub fn fingerprint_files(base: &Path, files: &[PathBuf]) -> Result<String> {
+    let mut hash = Fnv1a::default();
+    hash.write(env!("CARGO_PKG_VERSION").as_bytes());
+    for file in files {
+        let metadata = std::fs::metadata(base.join(file))
+            .with_context(|| format!("failed to read metadata of {}", file.display()))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_nanos());
+
+        hash.write(file.to_string_lossy().as_bytes());
+        hash.write(&metadata.len().to_le_bytes());
+        hash.write(&modified.to_le_bytes());
+    }
+    Ok(format!("{:016x}", hash.0))
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust versions.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Overview of all addressable bundles, to discover data bundles without guessing paths.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use indexmap::IndexMap;
+use rabex_env::Environment;
+use rabex_env::handle::{ScriptFilterContains, SerializedFileHandle};
+use rabex_env::rabex::objects::PPtr;
+use rabex_env::unity::types::{AssetBundle, MonoScript};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::addressables;
+use crate::warning::Warning;
+
+const RUNTIME_PATH: &str = "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/";
+
+#[derive(Debug, Serialize)]
+pub struct Catalog {
+    /// `catalog.json` if the bundles were listed from the catalog, otherwise `directory` for the build directory.
+    pub source: String,
+    pub bundles: Vec<BundleInfo>,
+    /// Bundles which couldn't be loaded and scripts whose names couldn't be read.
+    #[serde(skip)]
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleInfo {
+    /// Path relative to the build directory, as accepted by `load_addressables_bundle_content`.
+    pub bundle: PathBuf,
+    pub name: String,
+    /// The `m_Dependencies` of the bundle's `AssetBundle` object, not the dependencies recorded in the catalog.
+    pub dependencies: Vec<String>,
+    pub assets: Vec<String>,
+    /// Number of MonoBehaviours per script class name.
+    pub scripts: IndexMap<String, usize>,
+}
+
+/// Bundle paths relative to the build directory, from the `m_InternalIds` of a `catalog.json`.
+pub fn bundles_from_catalog(catalog: &Value) -> Vec<PathBuf> {
+    let Some(ids) = catalog["m_InternalIds"].as_array() else {
+        return Vec::new();
+    };
+    let mut bundles: Vec<PathBuf> = ids
+        .iter()
+        .filter_map(Value::as_str)
+        .filter(|id| id.ends_with(".bundle"))
+        .filter_map(|id| {
+            let id = id.replace('\\', "/");
+            // `{RuntimePath}/<platform>/<bundle>`
+            let (_, bundle) = id.strip_prefix(RUNTIME_PATH)?.split_once('/')?;
+            Some(PathBuf::from(bundle))
+        })
+        .collect();
+    bundles.sort();
+    bundles.dedup();
+    bundles
+}
+
+/// Bundle paths of the `catalog.json` in the `StreamingAssets/aa` directory, `None` if the game has no catalog.
+///
+/// Binary `catalog.bin` catalogs can't be read, so with only one of those this fails instead of listing bundles
+/// which may not match what the game loads.
+pub fn read_catalog_bundles(aa_dir: &Path) -> Result<Option<Vec<PathBuf>>> {
+    let json = aa_dir.join("catalog.json");
+    if json.exists() {
+        let catalog = serde_json::from_str(&std::fs::read_to_string(&json)?)?;
+        return Ok(Some(bundles_from_catalog(&catalog)));
+    }
+    if aa_dir.join("catalog.bin").exists() {
+        bail!(
+            "{} only has a binary catalog.bin, which isn't supported, \
+             list the bundles of the build directory instead",
+            aa_dir.display()
+        );
+    }
+    Ok(None)
+}
+
+impl Catalog {
+    /// Lists the bundles of the `catalog.json`, or every bundle in the build directory if there is no catalog.
+    ///
+    /// Fails for games which only have a binary `catalog.bin`, see [`Catalog::read_directory`].
+    pub fn read(env: &Environment) -> Result<Catalog> {
+        let aa_dir = env.game_files.game_dir.join("StreamingAssets/aa");
+        match read_catalog_bundles(&aa_dir)? {
+            Some(bundles) => Catalog::read_bundles(env, "catalog.json", bundles),
+            None => Catalog::read_directory(env),
+        }
+    }
+
+    /// Lists every bundle in the build directory, regardless of the catalog.
+    pub fn read_directory(env: &Environment) -> Result<Catalog> {
+        let bundles = addressables::bundles_in(env, Path::new(""))?;
+        Catalog::read_bundles(env, "directory", bundles)
+    }
+
+    fn read_bundles(
+        env: &Environment,
+        source: &str,
+        bundle_paths: Vec<PathBuf>,
+    ) -> Result<Catalog> {
+        let mut warnings = Vec::new();
+        let mut bundles = Vec::with_capacity(bundle_paths.len());
+        for bundle in bundle_paths {
+            let file = match env.load_addressables_bundle_content(&bundle) {
+                Ok(file) => file,
+                Err(e) => {
+                    warnings.push(Warning::new(
+                        bundle.display().to_string(),
+                        format!("couldn't load bundle: {e:#}"),
+                    ));
+                    continue;
+                }
+            };
+            bundles.push(BundleInfo::read(bundle, file, &mut warnings)?);
+        }
+
+        Ok(Catalog {
+            source: source.to_owned(),
+            bundles,
+            warnings,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct ScriptRef {
+    m_Script: PPtr,
+}
+
+impl BundleInfo {
+    fn read(
+        bundle: PathBuf,
+        file: SerializedFileHandle,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<BundleInfo> {
+        let (name, dependencies, assets) = match file.find_object_of::<AssetBundle>()? {
+            Some(asset_bundle) => (
+                asset_bundle.m_Name,
+                asset_bundle.m_Dependencies,
+                asset_bundle
+                    .m_Container
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect(),
+            ),
+            None => Default::default(),
+        };
+
+        let mut objects = Vec::new();
+        for object in file.scripts::<ScriptRef>(&ScriptFilterContains(""))? {
+            objects.push(object.read()?.m_Script);
+        }
+        let origin = bundle.display().to_string();
+        let scripts = count_scripts(
+            &objects,
+            |script| {
+                let script = file.deref_read_optional(script.typed::<MonoScript>())?;
+                Ok(script.map(|script| script.m_ClassName))
+            },
+            &origin,
+            warnings,
+        );
+
+        Ok(BundleInfo {
+            bundle,
+            name,
+            dependencies,
+            assets,
+            scripts,
+        })
+    }
+}
+
+/// Number of MonoBehaviours per script class name, most common first, for the `m_Script` of each MonoBehaviour.
+///
+/// `script_name` reads the class name of a script. Scripts which can't be read are counted under an empty name
+/// and reported as warnings about `bundle`.
+pub fn count_scripts(
+    scripts: &[PPtr],
+    mut script_name: impl FnMut(PPtr) -> Result<Option<String>>,
+    bundle: &str,
+    warnings: &mut Vec<Warning>,
+) -> IndexMap<String, usize> {
+    let mut script_names = HashMap::new();
+    let mut counts = IndexMap::new();
+    for &script in scripts {
+        let name: &String = script_names
+            .entry((script.m_FileID, script.m_PathID))
+            .or_insert_with(|| match script_name(script) {
+                Ok(name) => name.unwrap_or_default(),
+                Err(e) => {
+                    warnings.push(Warning::new(
+                        bundle,
+                        format!(
+                            "couldn't read script {}/{}: {e:#}",
+                            script.m_FileID, script.m_PathID
+                        ),
+                    ));
+                    String::new()
+                }
+            });
+        *counts.entry(name.clone()).or_insert(0) += 1;
+    }
+    counts.sort_by(|_, a, _, b| b.cmp(a));
+    counts
+}
@@ -0,0 +1,163 @@
+//! Completion requirements and the progress of a save file.
+//!
+//! This does not compute the in-game completion percentage. Of its contributors, only tools marked `isCounted`
+//! are modelled, so [`CompletionProgress::tool_percentage`] is the share of those tools that are unlocked.
+//! Required journal entries and quests are listed for reference, while mask shards, spool fragments and
+//! crests are not modelled at all.
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::enums::RequiredTypes;
+use crate::generated::{EnemyJournalRecord, Quest, ToolItemBasic};
+use crate::warning::Warning;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Category {
+    Journal,
+    Tools,
+    Quests,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Condition {
+    /// At least this many kills recorded in `EnemyJournalKillData`.
+    Kills(i32),
+    /// Unlocked in `Tools`.
+    ToolUnlocked,
+    /// Completed in `QuestCompletionData`.
+    QuestCompleted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Requirement {
+    pub category: Category,
+    pub name: String,
+    pub condition: Condition,
+}
+
+/// Everything that has to be done for 100% completion, see the [module documentation](self) for what is modelled.
+#[derive(Debug, Default, Serialize)]
+pub struct CompletionModel {
+    pub requirements: Vec<Requirement>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CategoryProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompletionProgress {
+    /// Percentage of the counted tools that are unlocked, 100 if there are none.
+    pub tool_percentage: f32,
+    pub categories: IndexMap<Category, CategoryProgress>,
+}
+
+impl CompletionModel {
+    /// Adds the record if it is required for completion. `RequiredSteelSoul` records only count in steel soul mode.
+    ///
+    /// Records with an unknown `requiredType` are skipped with a warning.
+    pub fn add_journal_record(
+        &mut self,
+        record: &EnemyJournalRecord,
+        steel_soul: bool,
+        warnings: &mut Vec<Warning>,
+    ) {
+        if record.isRequiredForCompletion == 0 || record.isAlwaysUnlocked != 0 {
+            return;
+        }
+        let required = match record.requiredType.try_into() {
+            Ok(RequiredTypes::NotRequired) => false,
+            Ok(RequiredTypes::Required) => true,
+            Ok(RequiredTypes::RequiredSteelSoul) => steel_soul,
+            Err(_) => {
+                warnings.push(Warning::new(
+                    &record.m_Name,
+                    format!("unknown requiredType {}", record.requiredType),
+                ));
+                false
+            }
+        };
+        if !required {
+            return;
+        }
+        self.requirements.push(Requirement {
+            category: Category::Journal,
+            name: record.m_Name.clone(),
+            condition: Condition::Kills(record.killsRequired.max(1)),
+        });
+    }
+
+    /// Adds the tool if it `isCounted` for completion.
+    pub fn add_tool(&mut self, tool: &ToolItemBasic) {
+        if tool.isCounted == 0 {
+            return;
+        }
+        self.requirements.push(Requirement {
+            category: Category::Tools,
+            name: tool.m_Name.clone(),
+            condition: Condition::ToolUnlocked,
+        });
+    }
+
+    pub fn add_quest(&mut self, quest: &Quest) {
+        self.requirements.push(Requirement {
+            category: Category::Quests,
+            name: quest.m_Name.clone(),
+            condition: Condition::QuestCompleted,
+        });
+    }
+
+    /// Computes the progress of a player in every category, given the `playerData` of a decoded save file.
+    pub fn progress(&self, player_data: &Value) -> CompletionProgress {
+        let mut categories: IndexMap<Category, CategoryProgress> = IndexMap::new();
+        for requirement in &self.requirements {
+            let category = categories.entry(requirement.category).or_default();
+            category.total += 1;
+            if requirement.is_met(player_data) {
+                category.completed += 1;
+            } else {
+                category.missing.push(requirement.name.clone());
+            }
+        }
+
+        let tool_percentage = match categories.get(&Category::Tools) {
+            Some(tools) if tools.total > 0 => tools.completed as f32 / tools.total as f32 * 100.0,
+            _ => 100.0,
+        };
+
+        CompletionProgress {
+            tool_percentage,
+            categories,
+        }
+    }
+}
+
+impl Requirement {
+    pub fn is_met(&self, player_data: &Value) -> bool {
+        match self.condition {
+            Condition::Kills(kills) => saved_data(player_data, "EnemyJournalKillData", &self.name)
+                .and_then(|data| data["Kills"].as_i64())
+                .is_some_and(|recorded| recorded >= kills as i64),
+            Condition::ToolUnlocked => saved_data(player_data, "Tools", &self.name)
+                .is_some_and(|data| data["IsUnlocked"].as_bool() == Some(true)),
+            Condition::QuestCompleted => saved_data(player_data, "QuestCompletionData", &self.name)
+                .is_some_and(|data| data["IsCompleted"].as_bool() == Some(true)),
+        }
+    }
+}
+
+/// Looks up an entry in one of the named lists of `PlayerData`, e.g. `{"Tools": {"savedData": [{"Name": .., "Data": ..}]}}`.
+fn saved_data<'a>(player_data: &'a Value, list: &str, name: &str) -> Option<&'a Value> {
+    let list = &player_data[list];
+    let entries = list
+        .get("savedData")
+        .or_else(|| list.get("list"))?
+        .as_array()?;
+    let entry = entries.iter().find(|entry| entry["Name"] == name)?;
+    entry.get("Data").or_else(|| entry.get("Record"))
+}
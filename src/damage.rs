@@ -0,0 +1,191 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::enums::{NailElement, SpecialDamageType};
+use crate::generated::{DamageTag, TimerGroup, ToolItemBasic};
+use crate::warning::Warning;
+
+/// Timing model of a [`DamageTag`], i.e. damage that is applied repeatedly over time.
+#[derive(Debug, Clone)]
+pub struct DamageModel {
+    pub name: String,
+    pub damage_amount: i32,
+    pub start_delay: f32,
+    pub delay_per_hit: f32,
+    /// `None` if the tag keeps hitting until removed.
+    pub hit_limit: Option<i32>,
+    /// Delay of the `TimerGroup` shared between everything applying this tag.
+    pub cooldown: Option<f32>,
+    pub nail_element: NailElement,
+    pub special_damage_type: SpecialDamageType,
+    pub is_tool_damage: bool,
+}
+
+impl DamageModel {
+    pub fn new(tag: &DamageTag, cooldown: Option<&TimerGroup>) -> Result<Self> {
+        Ok(DamageModel {
+            name: tag.m_Name.clone(),
+            damage_amount: tag.damageAmount,
+            start_delay: tag.startDelay,
+            delay_per_hit: tag.delayPerHit,
+            hit_limit: (tag.totalHitLimit > 0).then_some(tag.totalHitLimit),
+            cooldown: cooldown.map(|timer| timer.delay),
+            nail_element: tag.nailElement.try_into()?,
+            special_damage_type: tag.specialDamageType.try_into()?,
+            is_tool_damage: tag.isToolDamage != 0,
+        })
+    }
+
+    /// The same tag, limited to at most `hits` ticks. This is how tools apply their `poisonDamageTicks`/`zapDamageTicks`.
+    pub fn with_hit_limit(&self, hits: i32) -> Self {
+        DamageModel {
+            hit_limit: Some(self.hit_limit.map_or(hits, |limit| limit.min(hits))),
+            ..self.clone()
+        }
+    }
+
+    /// Time between two consecutive hits, or `None` if the tag only hits once.
+    pub fn interval(&self) -> Option<f32> {
+        if self.delay_per_hit > 0.0 {
+            Some(self.delay_per_hit)
+        } else {
+            self.cooldown.filter(|&cooldown| cooldown > 0.0)
+        }
+    }
+
+    pub fn dps(&self) -> Option<f32> {
+        self.interval()
+            .map(|interval| self.damage_amount as f32 / interval)
+    }
+
+    /// Time from application until the last hit, if the number of hits is limited.
+    pub fn duration(&self) -> Option<f32> {
+        let hits = self.hit_limit?;
+        let interval = self.interval().unwrap_or(0.0);
+        Some(self.start_delay + (hits - 1).max(0) as f32 * interval)
+    }
+
+    pub fn total_damage(&self) -> Option<i32> {
+        self.hit_limit.map(|hits| hits * self.damage_amount)
+    }
+
+    /// Number of hits landed within `seconds` after the tag was applied.
+    pub fn hits_within(&self, seconds: f32) -> i32 {
+        if seconds < self.start_delay {
+            return 0;
+        }
+        let hits = match self.interval() {
+            Some(interval) => ((seconds - self.start_delay) / interval).floor() as i32 + 1,
+            None => 1,
+        };
+        match self.hit_limit {
+            Some(limit) => hits.min(limit),
+            None => hits,
+        }
+    }
+
+    pub fn damage_within(&self, seconds: f32) -> i32 {
+        self.hits_within(seconds) * self.damage_amount
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct DamageComparison {
+    pub source: String,
+    pub damageTag: String,
+    pub nailElement: NailElement,
+    pub specialDamageType: SpecialDamageType,
+    pub damagePerHit: i32,
+    pub hits: Option<i32>,
+    pub startDelay: f32,
+    pub interval: Option<f32>,
+    pub duration: Option<f32>,
+    pub totalDamage: Option<i32>,
+    pub dps: Option<f32>,
+}
+
+impl DamageComparison {
+    pub fn new(source: String, model: &DamageModel) -> Self {
+        DamageComparison {
+            source,
+            damageTag: model.name.clone(),
+            nailElement: model.nail_element,
+            specialDamageType: model.special_damage_type,
+            damagePerHit: model.damage_amount,
+            hits: model.hit_limit,
+            startDelay: model.start_delay,
+            interval: model.interval(),
+            duration: model.duration(),
+            totalDamage: model.total_damage(),
+            dps: model.dps(),
+        }
+    }
+}
+
+/// The tags applied by the `poisonDamageTicks` and `zapDamageTicks` of tools.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ToolDamageTags<'a> {
+    pub poison: Option<&'a DamageModel>,
+    pub zap: Option<&'a DamageModel>,
+}
+
+impl<'a> ToolDamageTags<'a> {
+    /// Tools don't reference their tags, so this picks the only tool damage tag of each kind.
+    ///
+    /// If there is none or more than one, that kind is left out and a warning is added.
+    pub fn find(tags: &'a [DamageModel], warnings: &mut Vec<Warning>) -> Self {
+        let mut unique = |kind: &str, matches: &dyn Fn(&DamageModel) -> bool| {
+            let candidates: Vec<_> = tags
+                .iter()
+                .filter(|tag| tag.is_tool_damage && matches(tag))
+                .collect();
+            match candidates.as_slice() {
+                [tag] => Some(*tag),
+                _ => {
+                    let names: Vec<_> = candidates.iter().map(|tag| tag.name.as_str()).collect();
+                    warnings.push(Warning::new(
+                        format!("{kind} tool damage"),
+                        format!("expected one damage tag, found [{}]", names.join(", ")),
+                    ));
+                    None
+                }
+            }
+        };
+        ToolDamageTags {
+            poison: unique("poison", &|tag| tag.nail_element == NailElement::Poison),
+            zap: unique("zap", &|tag| {
+                tag.special_damage_type == SpecialDamageType::Lightning
+            }),
+        }
+    }
+}
+
+/// Compares every damage tag on its own, and the poison and lightning tags as applied by each tool.
+pub fn compare(
+    tags: &[DamageModel],
+    tool_tags: ToolDamageTags,
+    tools: &[(String, ToolItemBasic)],
+) -> Vec<DamageComparison> {
+    let mut rows: Vec<_> = tags
+        .iter()
+        .map(|tag| DamageComparison::new(String::new(), tag))
+        .collect();
+
+    for (tool_name, tool) in tools {
+        let applied = [
+            (tool_tags.poison, tool.poisonDamageTicks),
+            (tool_tags.zap, tool.zapDamageTicks),
+        ];
+        for (tag, ticks) in applied {
+            if let Some(tag) = tag.filter(|_| ticks > 0) {
+                rows.push(DamageComparison::new(
+                    tool_name.clone(),
+                    &tag.with_hit_limit(ticks),
+                ));
+            }
+        }
+    }
+
+    rows
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
+use rabex_env::handle::SerializedFileHandle;
+use serde::Serialize;
+
+use crate::generated::{DamageHero, EnemyJournalRecord, HealthManager};
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct Enemy {
+    pub name: String,
+    pub journalRecord: Option<String>,
+    pub hp: i32,
+    /// Damage dealt to the player on contact, from a `DamageHero` on the same GameObject.
+    pub contactDamage: Option<i32>,
+    pub smallRosaryDrops: i32,
+    pub mediumRosaryDrops: i32,
+    pub largeRosaryDrops: i32,
+    pub largeSmoothRosaryDrops: i32,
+    pub shardDrops: i32,
+    /// Scenes or bundles the enemy was found in.
+    pub foundIn: IndexSet<String>,
+}
+
+/// Enemies found in scenes and prefab bundles, keyed by GameObject name.
+///
+/// Enemies are identified by their `HealthManager`, stats are taken from the first instance found.
+#[derive(Debug, Default)]
+pub struct EnemyIndex {
+    pub enemies: IndexMap<String, Enemy>,
+}
+
+impl EnemyIndex {
+    pub fn add_file(&mut self, source: &str, file: SerializedFileHandle) -> Result<()> {
+        let mut contact_damage = HashMap::new();
+        for damage_hero in file.scripts::<DamageHero>(&"DamageHero")? {
+            let damage_hero = damage_hero.read()?;
+            contact_damage.insert(damage_hero.m_GameObject.m_PathID, damage_hero.damageDealt);
+        }
+
+        for health_manager in file.scripts::<HealthManager>(&"HealthManager")? {
+            let health_manager = health_manager.read()?;
+            let Some(go) = file.deref_read_optional(health_manager.m_GameObject)? else {
+                continue;
+            };
+            let damage = contact_damage
+                .get(&health_manager.m_GameObject.m_PathID)
+                .copied();
+            self.add_enemy(source, &go.m_Name, &health_manager, damage);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the enemy with the `HealthManager` on the GameObject `game_object`, unless one with the same name exists.
+    pub fn add_enemy(
+        &mut self,
+        source: &str,
+        game_object: &str,
+        health_manager: &HealthManager,
+        contact_damage: Option<i32>,
+    ) {
+        let name = normalize_name(game_object).to_owned();
+        let enemy = self.enemies.entry(name.clone()).or_insert_with(|| Enemy {
+            name,
+            journalRecord: None,
+            hp: health_manager.hp,
+            contactDamage: contact_damage,
+            smallRosaryDrops: health_manager.smallGeoDrops.unwrap_or_default(),
+            mediumRosaryDrops: health_manager.mediumGeoDrops.unwrap_or_default(),
+            largeRosaryDrops: health_manager.largeGeoDrops.unwrap_or_default(),
+            largeSmoothRosaryDrops: health_manager.largeSmoothGeoDrops.unwrap_or_default(),
+            shardDrops: health_manager.shellShardDrops.unwrap_or_default(),
+            foundIn: IndexSet::new(),
+        });
+        enemy.foundIn.insert(source.to_owned());
+    }
+
+    /// Links enemies to the journal record with the same name, ignoring case and whitespace.
+    pub fn link_journal(&mut self, records: &[EnemyJournalRecord]) {
+        let records: HashMap<_, _> = records
+            .iter()
+            .map(|record| (comparable_name(&record.m_Name), &record.m_Name))
+            .collect();
+        for enemy in self.enemies.values_mut() {
+            enemy.journalRecord = records
+                .get(&comparable_name(&enemy.name))
+                .map(|&name| name.clone());
+        }
+    }
+}
+
+/// Strips the suffixes unity appends to duplicated or instantiated objects, e.g. `Crawler (2)` or `Crawler(Clone)`.
+fn normalize_name(name: &str) -> &str {
+    let mut name = name.trim();
+    loop {
+        let stripped = name.strip_suffix("(Clone)").unwrap_or(name).trim_end();
+        let stripped = strip_copy_number(stripped).unwrap_or(stripped);
+        if stripped == name {
+            return name;
+        }
+        name = stripped;
+    }
+}
+
+fn strip_copy_number(name: &str) -> Option<&str> {
+    let (base, n) = name.strip_suffix(')')?.rsplit_once(" (")?;
+    n.chars().all(|c| c.is_ascii_digit()).then_some(base)
+}
+
+fn comparable_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
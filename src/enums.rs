@@ -18,14 +18,14 @@ pub enum RequiredTypes {
     Required,
     RequiredSteelSoul,
 }
-#[derive(TryFromPrimitive, Debug, Serialize, Deserialize_repr)]
+#[derive(TryFromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize_repr)]
 #[repr(i32)]
 pub enum NailElement {
     None,
     Fire,
     Poison,
 }
-#[derive(TryFromPrimitive, Debug, Serialize, Deserialize_repr)]
+#[derive(TryFromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize_repr)]
 #[repr(i32)]
 pub enum SpecialDamageType {
     None,
@@ -82,3 +82,10 @@ pub enum ReplenishResources {
     Money,
     Shard,
 }
+
+#[derive(TryFromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize_repr)]
+#[repr(i32)]
+pub enum CurrencyType {
+    Money,
+    Shard,
+}
@@ -0,0 +1,428 @@
+//! Reading of PlayMaker FSMs.
+//!
+//! Action parameters are serialized in parallel arrays in `ActionData`, this module
+//! decodes them into one JSON value per parameter.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use rabex_env::handle::SerializedFileHandle;
+use rabex_env::rabex::objects::TypedPPtr;
+use rabex_env::unity::types::GameObject;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::dot::quote;
+use crate::warning::Warning;
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct Fsm {
+    pub gameObject: String,
+    pub name: String,
+    pub startState: String,
+    pub events: Vec<String>,
+    pub globalTransitions: Vec<Transition>,
+    pub states: Vec<State>,
+    pub variables: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct State {
+    pub name: String,
+    pub transitions: Vec<Transition>,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct Transition {
+    pub event: String,
+    pub toState: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Action {
+    /// Class name of the action, without namespace.
+    pub r#type: String,
+    pub name: Option<String>,
+    pub enabled: bool,
+    pub params: IndexMap<String, Value>,
+}
+
+impl Fsm {
+    /// Reads all `PlayMakerFSM` components of `file`, collecting undecodable action parameters in `warnings`.
+    pub fn read_all(file: SerializedFileHandle, warnings: &mut Vec<Warning>) -> Result<Vec<Fsm>> {
+        let mut fsms = Vec::new();
+        for component in file.scripts::<PlayMakerFSM>(&"PlayMakerFSM")? {
+            let component = component.read()?;
+            let game_object = file
+                .deref_read_optional(component.m_GameObject)?
+                .map(|go| go.m_Name)
+                .unwrap_or_default();
+            fsms.push(Fsm::new(game_object, component.fsm, warnings));
+        }
+        Ok(fsms)
+    }
+
+    /// Decodes the `fsm` field of a `PlayMakerFSM`, e.g. from a type tree dump.
+    pub fn from_value(game_object: String, fsm: Value, warnings: &mut Vec<Warning>) -> Result<Fsm> {
+        Ok(Fsm::new(
+            game_object,
+            serde_json::from_value(fsm)?,
+            warnings,
+        ))
+    }
+
+    fn new(game_object: String, fsm: FsmData, warnings: &mut Vec<Warning>) -> Fsm {
+        let object = format!("{} - {}", game_object, fsm.name);
+        Fsm {
+            gameObject: game_object,
+            name: fsm.name,
+            startState: fsm.startState,
+            events: fsm.events.into_iter().map(|event| event.name).collect(),
+            globalTransitions: fsm
+                .globalTransitions
+                .into_iter()
+                .map(Transition::from)
+                .collect(),
+            states: fsm
+                .states
+                .into_iter()
+                .map(|state| State {
+                    actions: state.actionData.actions(&mut |message| {
+                        warnings.push(Warning::new(
+                            &object,
+                            format!("state '{}': {message}", state.name),
+                        ))
+                    }),
+                    name: state.name,
+                    transitions: state
+                        .transitions
+                        .into_iter()
+                        .map(Transition::from)
+                        .collect(),
+                })
+                .collect(),
+            variables: fsm.variables,
+        }
+    }
+
+    /// All events sent by actions of this FSM, e.g. by `SendEvent`.
+    pub fn sent_events(&self) -> impl Iterator<Item = &str> {
+        self.states
+            .iter()
+            .flat_map(|state| &state.actions)
+            .flat_map(|action| action.params.values())
+            .filter_map(|param| param.get("event")?.as_str())
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!(
+            "digraph {} {{\n",
+            quote(&format!("{} - {}", self.gameObject, self.name))
+        );
+        for state in &self.states {
+            let style = match state.name == self.startState {
+                true => " [style=bold]",
+                false => "",
+            };
+            writeln!(dot, "    {}{style};", quote(&state.name)).unwrap();
+        }
+        for transition in &self.globalTransitions {
+            writeln!(
+                dot,
+                "    \"<global>\" -> {} [label={} style=dashed];",
+                quote(&transition.toState),
+                quote(&transition.event)
+            )
+            .unwrap();
+        }
+        for state in &self.states {
+            for transition in &state.transitions {
+                writeln!(
+                    dot,
+                    "    {} -> {} [label={}];",
+                    quote(&state.name),
+                    quote(&transition.toState),
+                    quote(&transition.event)
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl From<FsmTransitionData> for Transition {
+    fn from(value: FsmTransitionData) -> Self {
+        Transition {
+            event: value.fsmEvent.name,
+            toState: value.toState,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct PlayMakerFSM {
+    m_GameObject: TypedPPtr<GameObject>,
+    fsm: FsmData,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct FsmData {
+    name: String,
+    startState: String,
+    states: Vec<FsmStateData>,
+    events: Vec<FsmEventData>,
+    globalTransitions: Vec<FsmTransitionData>,
+    variables: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct FsmStateData {
+    name: String,
+    transitions: Vec<FsmTransitionData>,
+    actionData: ActionData,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct FsmTransitionData {
+    fsmEvent: FsmEventData,
+    toState: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FsmEventData {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct ActionData {
+    actionNames: Vec<String>,
+    customNames: Vec<String>,
+    actionEnabled: Vec<u8>,
+    actionStartIndex: Vec<i32>,
+    paramName: Vec<String>,
+    paramDataType: Vec<i32>,
+    paramDataPos: Vec<i32>,
+    paramByteDataSize: Vec<i32>,
+    byteData: Vec<u8>,
+    /// `fsmIntParams`, `unityObjectParams` etc.
+    #[serde(flatten)]
+    lists: HashMap<String, Value>,
+}
+
+impl ActionData {
+    fn actions(&self, warn: &mut impl FnMut(String)) -> Vec<Action> {
+        let mut actions = Vec::with_capacity(self.actionNames.len());
+        for (i, action_name) in self.actionNames.iter().enumerate() {
+            let mut params = IndexMap::new();
+            match self.actionStartIndex.get(i) {
+                Some(&start) => {
+                    let end = self
+                        .actionStartIndex
+                        .get(i + 1)
+                        .map_or(self.paramName.len(), |&end| end as usize);
+                    for param in start as usize..end {
+                        let Some(name) = self.paramName.get(param) else {
+                            warn(format!(
+                                "action {action_name}: parameter {param} is missing from paramName"
+                            ));
+                            break;
+                        };
+                        let value = self.param(param, &mut |message| {
+                            warn(format!("action {action_name}: {message}"))
+                        });
+                        params.insert(name.clone(), value);
+                    }
+                }
+                None => warn(format!(
+                    "action {action_name} is missing from actionStartIndex"
+                )),
+            }
+
+            actions.push(Action {
+                r#type: action_name
+                    .rsplit('.')
+                    .next()
+                    .unwrap_or(action_name)
+                    .to_owned(),
+                name: self
+                    .customNames
+                    .get(i)
+                    .filter(|name| !name.is_empty())
+                    .cloned(),
+                enabled: self
+                    .actionEnabled
+                    .get(i)
+                    .is_none_or(|&enabled| enabled != 0),
+                params,
+            });
+        }
+        actions
+    }
+
+    /// Decodes parameter `i`. Unknown types and malformed data are reported as warnings and decode to `null`.
+    fn param(&self, i: usize, warn: &mut impl FnMut(String)) -> Value {
+        use param_data_type::*;
+
+        let name = self.paramName.get(i).map_or("", String::as_str);
+        let mut get = |list: &[i32], list_name: &str| {
+            let item = list.get(i).copied();
+            if item.is_none() {
+                warn(format!("parameter '{name}' is missing from {list_name}"));
+            }
+            item
+        };
+        let (Some(pos), Some(size), Some(data_type)) = (
+            get(&self.paramDataPos, "paramDataPos"),
+            get(&self.paramByteDataSize, "paramByteDataSize"),
+            get(&self.paramDataType, "paramDataType"),
+        ) else {
+            return Value::Null;
+        };
+        let pos = pos as usize;
+        let bytes = pos
+            .checked_add(size as usize)
+            .and_then(|end| self.byteData.get(pos..end))
+            .unwrap_or_default();
+        let mut sized = |len: usize| match bytes.len() == len {
+            true => Some(bytes),
+            false => {
+                warn(format!(
+                    "parameter '{name}' has {} bytes, expected {len}",
+                    bytes.len()
+                ));
+                None
+            }
+        };
+        let floats = |bytes: &[u8]| -> Value {
+            bytes
+                .chunks_exact(4)
+                .map(|f| Value::from(f32::from_le_bytes(f.try_into().unwrap())))
+                .collect()
+        };
+
+        match data_type {
+            INTEGER | LAYER_MASK | ENUM => sized(4)
+                .map(|b| i32::from_le_bytes(b.try_into().unwrap()).into())
+                .unwrap_or_default(),
+            BOOLEAN => Value::Bool(bytes.first().is_some_and(|&b| b != 0)),
+            FLOAT => sized(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()).into())
+                .unwrap_or_default(),
+            VECTOR2 => sized(8).map(floats).unwrap_or_default(),
+            VECTOR3 => sized(12).map(floats).unwrap_or_default(),
+            COLOR | VECTOR4 | RECT | QUATERNION => sized(16).map(floats).unwrap_or_default(),
+            STRING => String::from_utf8_lossy(bytes).into_owned().into(),
+            CHARACTER => sized(2)
+                .map(|b| {
+                    let c = u16::from_le_bytes(b.try_into().unwrap());
+                    String::from_utf16_lossy(&[c]).into()
+                })
+                .unwrap_or_default(),
+            FSM_EVENT => serde_json::json!({ "event": String::from_utf8_lossy(bytes) }),
+            OBJECT_REFERENCE | GAME_OBJECT => self.list_item("unityObjectParams", pos),
+            FSM_INT => self.list_item("fsmIntParams", pos),
+            FSM_FLOAT => self.list_item("fsmFloatParams", pos),
+            FSM_BOOL => self.list_item("fsmBoolParams", pos),
+            FSM_STRING => self.list_item("fsmStringParams", pos),
+            FSM_GAME_OBJECT => self.list_item("fsmGameObjectParams", pos),
+            FSM_OWNER_DEFAULT => self.list_item("fsmOwnerDefaultParams", pos),
+            FSM_VECTOR2 => self.list_item("fsmVector2Params", pos),
+            FSM_VECTOR3 => self.list_item("fsmVector3Params", pos),
+            FSM_COLOR => self.list_item("fsmColorParams", pos),
+            FSM_RECT => self.list_item("fsmRectParams", pos),
+            FSM_QUATERNION => self.list_item("fsmQuaternionParams", pos),
+            FSM_OBJECT | FSM_MATERIAL | FSM_TEXTURE => self.list_item("fsmObjectParams", pos),
+            FSM_EVENT_TARGET => self.list_item("fsmEventTargetParams", pos),
+            ANIMATION_CURVE | FSM_ANIMATION_CURVE => self.list_item("animationCurveParams", pos),
+            FUNCTION_CALL => self.list_item("functionCallParams", pos),
+            LAYOUT_OPTION => self.list_item("layoutOptionParams", pos),
+            FSM_PROPERTY => self.list_item("fsmPropertyParams", pos),
+            FSM_TEMPLATE_CONTROL => self.list_item("fsmTemplateControlParams", pos),
+            FSM_VAR => self.list_item("fsmVarParams", pos),
+            FSM_ARRAY => self.list_item("fsmArrayParams", pos),
+            FSM_ENUM => self.list_item("fsmEnumParams", pos),
+            ARRAY => serde_json::json!({
+                "arraySize": self.list_item("arrayParamSizes", pos),
+                "arrayType": self.list_item("arrayParamTypes", pos),
+            }),
+            CUSTOM_CLASS => serde_json::json!({
+                "typeName": self.list_item("customTypeNames", pos),
+                "typeSize": self.list_item("customTypeSizes", pos),
+            }),
+            UNSUPPORTED => Value::Null,
+            other => {
+                warn(format!(
+                    "parameter '{name}' has unknown ParamDataType {other}"
+                ));
+                Value::Null
+            }
+        }
+    }
+
+    fn list_item(&self, list: &str, pos: usize) -> Value {
+        self.lists
+            .get(list)
+            .and_then(|list| list.get(pos))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Values of PlayMaker's `ParamDataType`.
+mod param_data_type {
+    pub const INTEGER: i32 = 0;
+    pub const BOOLEAN: i32 = 1;
+    pub const FLOAT: i32 = 2;
+    pub const STRING: i32 = 3;
+    pub const COLOR: i32 = 4;
+    pub const OBJECT_REFERENCE: i32 = 5;
+    pub const LAYER_MASK: i32 = 6;
+    pub const ENUM: i32 = 7;
+    pub const VECTOR2: i32 = 8;
+    pub const VECTOR3: i32 = 9;
+    pub const VECTOR4: i32 = 10;
+    pub const RECT: i32 = 11;
+    pub const ARRAY: i32 = 12;
+    pub const CHARACTER: i32 = 13;
+    pub const ANIMATION_CURVE: i32 = 14;
+    pub const FSM_FLOAT: i32 = 15;
+    pub const FSM_INT: i32 = 16;
+    pub const FSM_BOOL: i32 = 17;
+    pub const FSM_STRING: i32 = 18;
+    pub const FSM_GAME_OBJECT: i32 = 19;
+    pub const FSM_OWNER_DEFAULT: i32 = 20;
+    pub const FUNCTION_CALL: i32 = 21;
+    pub const FSM_ANIMATION_CURVE: i32 = 22;
+    pub const FSM_EVENT: i32 = 23;
+    pub const FSM_OBJECT: i32 = 24;
+    pub const FSM_COLOR: i32 = 25;
+    pub const UNSUPPORTED: i32 = 26;
+    pub const GAME_OBJECT: i32 = 27;
+    pub const FSM_VECTOR3: i32 = 28;
+    pub const LAYOUT_OPTION: i32 = 29;
+    pub const FSM_RECT: i32 = 30;
+    pub const FSM_EVENT_TARGET: i32 = 31;
+    pub const FSM_MATERIAL: i32 = 32;
+    pub const FSM_TEXTURE: i32 = 33;
+    pub const QUATERNION: i32 = 34;
+    pub const FSM_QUATERNION: i32 = 35;
+    pub const FSM_PROPERTY: i32 = 36;
+    pub const FSM_VECTOR2: i32 = 37;
+    pub const FSM_TEMPLATE_CONTROL: i32 = 38;
+    pub const FSM_VAR: i32 = 39;
+    pub const CUSTOM_CLASS: i32 = 40;
+    pub const FSM_ARRAY: i32 = 41;
+    pub const FSM_ENUM: i32 = 42;
+}
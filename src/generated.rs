@@ -548,3 +548,26 @@ pub struct Vector2 {
     pub x: f32,
     pub y: f32,
 }
+
+#[derive(Debug, serde::Deserialize)]
+pub struct HealthManager {
+    pub m_GameObject: TypedPPtr<GameObject>,
+    pub m_Enabled: u8,
+    pub m_Script: TypedPPtr<MonoScript>,
+    pub m_Name: String,
+    pub hp: i32,
+    pub smallGeoDrops: Option<i32>,
+    pub mediumGeoDrops: Option<i32>,
+    pub largeGeoDrops: Option<i32>,
+    pub largeSmoothGeoDrops: Option<i32>,
+    pub shellShardDrops: Option<i32>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct DamageHero {
+    pub m_GameObject: TypedPPtr<GameObject>,
+    pub m_Enabled: u8,
+    pub m_Script: TypedPPtr<MonoScript>,
+    pub m_Name: String,
+    pub damageDealt: i32,
+}
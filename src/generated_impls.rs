@@ -1,20 +1,69 @@
 use std::fmt::Display;
 
-use crate::enums::{TestNumType, TestType};
+use anyhow::{Context, Result};
+
+use crate::enums::{ReplenishResources, ReplenishUsage, TestNumType, TestType};
 use crate::generated::*;
 use crate::lang::Language;
+use crate::warning::Warning;
+
+/// Amount of the replenish resource it costs to refill a tool with `Percentage` usage from empty.
+///
+/// The replenish costs aren't serialized in any asset but computed by the game's `ToolItem` code in
+/// `Assembly-CSharp.dll`, so they're mirrored here and have to be checked again after game updates.
+pub const FULL_REPLENISH_COST: f32 = 40.0;
+/// Cost of a single use of a tool with `OneForOne` usage, from the same game code as [`FULL_REPLENISH_COST`].
+pub const ONE_FOR_ONE_REPLENISH_COST: f32 = 1.0;
 
 impl LocalisedString {
-    pub fn get<'a>(&self, lang: &'a Language) -> &'a str {
-        assert!(!self.Sheet.is_empty());
-        assert!(!self.Key.is_empty());
-        &lang[&self.Sheet.to_ascii_lowercase()][&self.Key]
+    /// Looks up the string, failing if the sheet or key is empty or missing from `lang`.
+    pub fn get<'a>(&self, lang: &'a Language) -> Result<&'a str> {
+        self.lookup(lang)
+            .with_context(|| format!("missing localised string {}/{}", self.Sheet, self.Key))
     }
+    /// Looks up the string, returning `None` if it is unset or missing from `lang`.
     pub fn try_get<'a>(&self, lang: &'a Language) -> Option<&'a str> {
-        if self.Sheet.is_empty() && self.Key.is_empty() {
+        self.lookup(lang)
+    }
+    fn lookup<'a>(&self, lang: &'a Language) -> Option<&'a str> {
+        if self.Sheet.is_empty() || self.Key.is_empty() {
             return None;
         }
-        Some(&lang[&self.Sheet.to_ascii_lowercase()][&self.Key])
+        lang.get(&self.Sheet.to_ascii_lowercase())?
+            .get(&self.Key)
+            .map(String::as_str)
+    }
+}
+
+impl ToolItemBasic {
+    /// Cost of replenishing a single use of this tool, in units of `replenishResource`.
+    ///
+    /// Returns `None` for tools that aren't replenished, and for `Custom` usage, whose cost is computed by
+    /// tool specific game code. The latter also adds a warning, as the tool is replenished after all.
+    pub fn replenish_cost(&self, warnings: &mut Vec<Warning>) -> Result<Option<f32>> {
+        let resource: ReplenishResources = self.replenishResource.try_into()?;
+        if matches!(resource, ReplenishResources::None) {
+            return Ok(None);
+        }
+
+        let cost = match self.replenishUsage.try_into()? {
+            ReplenishUsage::Percentage => {
+                if self.baseStorageAmount == 0 {
+                    return Ok(None);
+                }
+                1.0 / self.baseStorageAmount as f32 * FULL_REPLENISH_COST
+            }
+            ReplenishUsage::OneForOne => ONE_FOR_ONE_REPLENISH_COST,
+            ReplenishUsage::Custom => {
+                warnings.push(Warning::new(
+                    &self.m_Name,
+                    "custom replenish usage, the cost isn't known",
+                ));
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(cost * self.replenishUsageMultiplier))
     }
 }
 
@@ -1,11 +1,16 @@
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
 use indexmap::IndexMap;
 use rabex_env::Environment;
 use rabex_env::rabex::objects::ClassId;
 use rabex_env::unity::types::{ResourceManager, TextAsset};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::addressables;
+use crate::warning::Warning;
 
 /*#[allow(non_snake_case)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,13 +28,206 @@ impl From<LocalizedString> for String {
 pub type Languages = IndexMap<String, Language>;
 pub type Language = IndexMap<String, IndexMap<String, String>>;
 
-pub fn get_language_keys(env: &Environment) -> Result<Languages> {
-    let ggm = env.load_cached("globalgamemanagers")?;
-    let resource_manager = ggm.find_object_of::<ResourceManager>()?.unwrap();
+/// Key used by the release builds of Silksong.
+pub const DEFAULT_KEY: &[u8; 32] = b"UKu52ePUBwetZ9wNX88o54dnfKRu0T1l";
+
+/// How language assets are found in the `ResourceManager` and decoded.
+#[derive(Debug, Clone)]
+pub struct LanguageConfig {
+    /// AES-256 keys tried in order when decrypting. The first one is used for encrypting,
+    /// if empty, assets are written as plain XML.
+    pub keys: Vec<Vec<u8>>,
+    /// Prefix of language resources in the `ResourceManager` container.
+    pub prefix: String,
+    pub naming: NamingScheme,
+    /// Directories relative to the addressables build directory to scan for language `TextAsset`s.
+    /// An empty path, the default, scans every bundle. The scene bundles are always skipped.
+    pub bundle_dirs: Vec<PathBuf>,
+    pub precedence: Precedence,
+}
+
+/// Which sheet wins if a sheet exists both in `globalgamemanagers` and an addressable bundle.
+///
+/// Between bundles, the last one in path order wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precedence {
+    Bundles,
+    GameManagers,
+}
+
+/// How the resource name of a language asset is made up of the language and the sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamingScheme {
+    /// `<lang><separator><sheet>`, e.g. `en_Tools`.
+    LanguageFirst(char),
+    /// `<sheet><separator><lang>`.
+    SheetFirst(char),
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        LanguageConfig {
+            keys: vec![DEFAULT_KEY.to_vec()],
+            prefix: "languages/".to_owned(),
+            naming: NamingScheme::LanguageFirst('_'),
+            bundle_dirs: vec![PathBuf::new()],
+            precedence: Precedence::Bundles,
+        }
+    }
+}
+
+impl NamingScheme {
+    /// Splits a resource name into language and sheet.
+    pub fn split<'a>(&self, name: &'a str) -> Option<(&'a str, &'a str)> {
+        let (lang, sheet) = match *self {
+            NamingScheme::LanguageFirst(separator) => name.split_once(separator)?,
+            NamingScheme::SheetFirst(separator) => {
+                let (sheet, lang) = name.rsplit_once(separator)?;
+                (lang, sheet)
+            }
+        };
+        (!lang.is_empty() && !sheet.is_empty()).then_some((lang, sheet))
+    }
+
+    pub fn join(&self, lang: &str, sheet: &str) -> String {
+        match *self {
+            NamingScheme::LanguageFirst(separator) => format!("{lang}{separator}{sheet}"),
+            NamingScheme::SheetFirst(separator) => format!("{sheet}{separator}{lang}"),
+        }
+    }
+}
+
+/// The encoding a language asset was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetFormat {
+    PlainXml,
+    Base64Xml,
+    /// Encrypted with the key at this index of [`LanguageConfig::keys`].
+    Encrypted(usize),
+}
+
+/// Origin of sheets loaded from the `ResourceManager`.
+pub const GAME_MANAGERS: &str = "globalgamemanagers";
+
+/// Language sheets together with the file each sheet was loaded from.
+#[derive(Debug, Default)]
+pub struct LoadedLanguages {
+    pub languages: Languages,
+    /// Origin of every sheet by language and sheet, either [`GAME_MANAGERS`] or a bundle path.
+    pub origins: IndexMap<String, IndexMap<String, String>>,
+    /// Conflicting sheets between bundles and bundles which couldn't be scanned.
+    pub warnings: Vec<Warning>,
+}
+
+impl LoadedLanguages {
+    /// Adds the language resource `name` of the `ResourceManager`, without the [`LanguageConfig::prefix`].
+    pub fn add_resource(
+        &mut self,
+        name: &str,
+        script: &str,
+        config: &LanguageConfig,
+    ) -> Result<()> {
+        let (lang, sheet) = config.naming.split(name).with_context(|| {
+            format!(
+                "language resource '{}{name}' doesn't match the naming scheme {:?}",
+                config.prefix, config.naming
+            )
+        })?;
+        let (language_keys, _) = decode_language_asset(script, config)
+            .with_context(|| format!("Decoding language resource '{}{name}'", config.prefix))?;
+
+        self.insert(GAME_MANAGERS, lang, sheet, language_keys, config.precedence);
+        Ok(())
+    }
+
+    /// Adds the `TextAsset` `name` of the bundle `origin` if it is named like a language sheet and decodes as one.
+    ///
+    /// Decoding errors are only returned for languages which are already known, other `TextAsset`s are skipped.
+    pub fn add_bundle_asset(
+        &mut self,
+        origin: &str,
+        name: &str,
+        script: &str,
+        config: &LanguageConfig,
+    ) -> Result<()> {
+        let Some((lang, sheet)) = config.naming.split(name) else {
+            return Ok(());
+        };
+        match decode_language_asset(script, config) {
+            Ok((keys, _)) if !keys.is_empty() => {
+                self.insert(origin, lang, sheet, keys, config.precedence);
+                Ok(())
+            }
+            // a language we already know, so this is likely a broken language asset
+            Err(e) if self.languages.contains_key(&lang.to_lowercase()) => {
+                Err(e.context(format!("couldn't decode {name}")))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds a sheet according to the `precedence`. Returns whether it was inserted.
+    ///
+    /// Language and sheet are lowercased like the `ResourceManager` container paths, so e.g. a bundle asset
+    /// `EN_Tools` replaces the resource `en_tools`.
+    pub fn insert(
+        &mut self,
+        origin: &str,
+        lang: &str,
+        sheet: &str,
+        keys: IndexMap<String, String>,
+        precedence: Precedence,
+    ) -> bool {
+        let (lang, sheet) = (lang.to_lowercase(), sheet.to_lowercase());
+        let origins = self.origins.entry(lang.clone()).or_default();
+        if let Some(existing) = origins.get(&sheet) {
+            let replace = match (existing == GAME_MANAGERS, origin == GAME_MANAGERS) {
+                (true, false) => precedence == Precedence::Bundles,
+                (false, true) => precedence == Precedence::GameManagers,
+                _ => {
+                    self.warnings.push(Warning::new(
+                        format!("{lang}/{sheet}"),
+                        format!("language sheet exists in both {existing} and {origin}"),
+                    ));
+                    true
+                }
+            };
+            if !replace {
+                return false;
+            }
+        }
+
+        origins.insert(sheet.clone(), origin.to_owned());
+        self.languages.entry(lang).or_default().insert(sheet, keys);
+        true
+    }
+}
+
+pub fn get_language_keys(env: &Environment, warnings: &mut Vec<Warning>) -> Result<Languages> {
+    get_language_keys_with(env, &LanguageConfig::default(), warnings)
+}
+
+pub fn get_language_keys_with(
+    env: &Environment,
+    config: &LanguageConfig,
+    warnings: &mut Vec<Warning>,
+) -> Result<Languages> {
+    let loaded = load_languages(env, config)?;
+    warnings.extend(loaded.warnings);
+    Ok(loaded.languages)
+}
+
+/// Loads the language assets of the `ResourceManager` and of the configured addressable bundles.
+pub fn load_languages(env: &Environment, config: &LanguageConfig) -> Result<LoadedLanguages> {
+    let mut loaded = LoadedLanguages::default();
+
+    let ggm = env.load_cached(GAME_MANAGERS)?;
+    let resource_manager = ggm
+        .find_object_of::<ResourceManager>()?
+        .context("globalgamemanagers contains no ResourceManager")?;
 
-    let mut languages = Languages::new();
     for (name, resource) in resource_manager.m_Container {
-        let Some(name) = name.strip_prefix("languages/") else {
+        let Some(name) = name.strip_prefix(&config.prefix) else {
             continue;
         };
         let data = ggm.deref(resource.typed::<TextAsset>())?;
@@ -37,47 +235,177 @@ pub fn get_language_keys(env: &Environment) -> Result<Languages> {
             continue;
         }
 
-        let key = b"UKu52ePUBwetZ9wNX88o54dnfKRu0T1l";
-        let (lang, sheet) = name.split_once('_').unwrap();
-        let data = data.read()?;
-        let data = BASE64_STANDARD.decode(data.m_Script)?;
-        let data = decrypt(key, &data)?;
-
-        let language_keys = quick_xml::de::from_str::<LanguageAsset>(&data)?;
-        let language_keys = language_keys
-            .entries
-            .into_iter()
-            .map(|entry| (entry.name, entry.value))
-            .collect();
-
-        languages
-            .entry(lang.to_owned())
-            .or_default()
-            .insert(sheet.to_owned(), language_keys);
+        loaded.add_resource(name, &data.read()?.m_Script, config)?;
+    }
+
+    for dir in &config.bundle_dirs {
+        for bundle in addressables::bundles_in(env, dir)? {
+            if bundle.starts_with(addressables::SCENES_DIR) {
+                continue;
+            }
+            if let Err(e) = add_bundle_languages(env, &bundle, config, &mut loaded) {
+                loaded.warnings.push(Warning::new(
+                    bundle.display().to_string(),
+                    format!("skipped while scanning for language assets: {e:#}"),
+                ));
+            }
+        }
+    }
+
+    Ok(loaded)
+}
+
+/// Adds every `TextAsset` of the bundle named like a language sheet which decodes as one.
+fn add_bundle_languages(
+    env: &Environment,
+    bundle: &Path,
+    config: &LanguageConfig,
+    loaded: &mut LoadedLanguages,
+) -> Result<()> {
+    let file = env.load_addressables_bundle_content(bundle)?;
+    let origin = bundle.display().to_string();
+    for text_asset in file.objects_of::<TextAsset>()? {
+        let text_asset = text_asset.read()?;
+        if let Err(e) =
+            loaded.add_bundle_asset(&origin, &text_asset.m_Name, &text_asset.m_Script, config)
+        {
+            loaded
+                .warnings
+                .push(Warning::new(&origin, format!("{e:#}")));
+        }
+    }
+    Ok(())
+}
+
+/// Decodes the `m_Script` of a language `TextAsset` into its keys and values.
+pub fn read_language_asset(script: &str) -> Result<IndexMap<String, String>> {
+    decode_language_asset(script, &LanguageConfig::default()).map(|(keys, _)| keys)
+}
+
+/// Decodes a language asset, detecting whether it is plain XML, base64 encoded XML,
+/// or encrypted with one of the configured keys.
+pub fn decode_language_asset(
+    script: &str,
+    config: &LanguageConfig,
+) -> Result<(IndexMap<String, String>, AssetFormat)> {
+    let script = script.trim();
+    if script.starts_with('<') {
+        return Ok((parse_xml(script)?, AssetFormat::PlainXml));
+    }
+
+    let data = BASE64_STANDARD
+        .decode(script)
+        .context("language asset is neither XML nor base64")?;
+    if let Ok(xml) = std::str::from_utf8(&data)
+        && xml.trim_start().starts_with('<')
+    {
+        return Ok((parse_xml(xml)?, AssetFormat::Base64Xml));
     }
 
-    Ok(languages)
+    let mut errors = Vec::new();
+    for (i, key) in config.keys.iter().enumerate() {
+        match decrypt(key, &data).and_then(|xml| parse_xml(&xml)) {
+            Ok(keys) => return Ok((keys, AssetFormat::Encrypted(i))),
+            Err(e) => errors.push(format!("key {i}: {e:#}")),
+        }
+    }
+    match errors.is_empty() {
+        true => bail!("language asset is encrypted, but no keys are configured"),
+        false => bail!(
+            "language asset couldn't be decrypted with any key ({})",
+            errors.join(", ")
+        ),
+    }
+}
+
+fn parse_xml(xml: &str) -> Result<IndexMap<String, String>> {
+    let language_keys = quick_xml::de::from_str::<LanguageAsset>(xml)?;
+    Ok(language_keys
+        .entries
+        .into_iter()
+        .map(|entry| (entry.name, entry.value))
+        .collect())
+}
+
+/// Encodes keys and values into the `m_Script` of a language `TextAsset`, the inverse of [`read_language_asset`].
+pub fn write_language_asset(keys: &IndexMap<String, String>) -> Result<String> {
+    write_language_asset_with(keys, &LanguageConfig::default())
+}
+
+pub fn write_language_asset_with(
+    keys: &IndexMap<String, String>,
+    config: &LanguageConfig,
+) -> Result<String> {
+    let asset = LanguageAsset {
+        entries: keys
+            .iter()
+            .map(|(name, value)| LanguageEntry {
+                name: name.clone(),
+                value: value.clone(),
+            })
+            .collect(),
+    };
+    let data = quick_xml::se::to_string_with_root("entries", &asset)?;
+    let Some(key) = config.keys.first() else {
+        return Ok(data);
+    };
+    let data = encrypt(key, data.as_bytes())?;
+
+    Ok(BASE64_STANDARD.encode(data))
+}
+
+/// Encodes every sheet of `language` into a `TextAsset` payload, keyed by resource name (`<lang>_<sheet>`).
+pub fn write_language_assets(lang: &str, language: &Language) -> Result<IndexMap<String, String>> {
+    write_language_assets_with(lang, language, &LanguageConfig::default())
+}
+
+pub fn write_language_assets_with(
+    lang: &str,
+    language: &Language,
+    config: &LanguageConfig,
+) -> Result<IndexMap<String, String>> {
+    language
+        .iter()
+        .map(|(sheet, keys)| {
+            Ok((
+                config.naming.join(lang, sheet),
+                write_language_asset_with(keys, config)?,
+            ))
+        })
+        .collect()
+}
+
+fn encrypt(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    use aes::cipher::block_padding::Pkcs7;
+    use aes::cipher::{BlockEncryptMut, KeyInit};
+
+    let encryptor = ecb::Encryptor::<aes::Aes256>::new_from_slice(key)
+        .map_err(|_| anyhow!("key must be 32 bytes long, got {}", key.len()))?;
+    Ok(encryptor.encrypt_padded_vec_mut::<Pkcs7>(data))
 }
 
 fn decrypt(key: &[u8], data: &[u8]) -> Result<String> {
     use aes::cipher::block_padding::Pkcs7;
     use aes::cipher::{BlockDecryptMut, KeyInit};
 
+    let decryptor = ecb::Decryptor::<aes::Aes256>::new_from_slice(key)
+        .map_err(|_| anyhow!("key must be 32 bytes long, got {}", key.len()))?;
     let mut out = vec![0; data.len()];
-    let pt = ecb::Decryptor::<aes::Aes256>::new(key.into())
-        .decrypt_padded_b2b_mut::<Pkcs7>(data, &mut out)?;
+    let pt = decryptor
+        .decrypt_padded_b2b_mut::<Pkcs7>(data, &mut out)
+        .map_err(|_| anyhow!("invalid padding"))?;
     let data = std::str::from_utf8(pt)?;
 
     Ok(data.to_owned())
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct LanguageAsset {
     #[serde(rename = "entry")]
     pub entries: Vec<LanguageEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct LanguageEntry {
     #[serde(rename = "@name")]
     pub name: String,
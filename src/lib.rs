@@ -1,7 +1,32 @@
+pub mod acquisition;
+pub mod addressables;
+pub mod args;
+pub mod cache;
+pub mod catalog;
+pub mod completion;
+pub mod damage;
+pub mod dot;
+pub mod enemies;
 pub mod enums;
+pub mod fsm;
 pub mod generated;
 pub mod generated_impls;
 pub mod lang;
+pub mod localised;
+pub mod metadata;
+pub mod parallel;
+pub mod playerdata;
+pub mod po;
+pub mod references;
+pub mod rooms;
+pub mod scenes;
+pub mod search;
+pub mod table_spec;
+pub mod tables;
+pub mod translation;
+pub mod typetree;
+pub mod warning;
+pub mod xliff;
 
 use anyhow::Result;
 use rabex_env::Environment;
@@ -9,10 +34,13 @@ use rabex_env::game_files::GameFiles;
 use rabex_env::rabex::tpk::TpkTypeTreeBlob;
 use rabex_env::rabex::typetree::typetree_cache::sync::TypeTreeCache;
 
-pub fn detect_game() -> Result<Option<Environment>> {
+use crate::metadata::SteamApp;
+
+pub fn detect_game() -> Result<Option<(Environment, SteamApp)>> {
     find_steam_game("Hollow Knight: Silksong")
 }
-pub fn find_steam_game(name: &str) -> Result<Option<Environment>> {
+/// Finds the installed Steam app whose name contains `name`, together with the app it was found as.
+pub fn find_steam_game(name: &str) -> Result<Option<(Environment, SteamApp)>> {
     let name_filter = name.to_lowercase();
 
     let steam = steamlocate::SteamDir::locate()?;
@@ -35,7 +63,11 @@ pub fn find_steam_game(name: &str) -> Result<Option<Environment>> {
             {
                 let tpk = TypeTreeCache::new(TpkTypeTreeBlob::embedded());
                 let env = Environment::new(game_files, tpk);
-                return Ok(Some(env));
+                let steam_app = SteamApp {
+                    app_id: app.app_id,
+                    build_id: app.build_id,
+                };
+                return Ok(Some((env, steam_app)));
             }
         }
     }
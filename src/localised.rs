@@ -0,0 +1,100 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::lang::Language;
+use crate::references::Usage;
+use crate::typetree;
+
+/// Reverse index from `LocalisedString` sheets and keys to every object pointing to them.
+///
+/// `LocalisedStringPlural`s are included as their `Plural` and `Single` fields.
+/// Sheets are lowercased, like the sheet names of [`Language`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LocalisedIndex {
+    pub sheets: IndexMap<String, IndexMap<String, Vec<Usage>>>,
+}
+
+impl LocalisedIndex {
+    /// Adds all `LocalisedString` fields of an object read as [`Value`].
+    pub fn add_value(&mut self, bundle: &str, script: &str, value: &Value) {
+        let strings = collect_localised_strings(value);
+
+        let name = value["m_Name"].as_str().unwrap_or_default();
+        for (field, sheet, key) in strings {
+            self.sheets
+                .entry(sheet.to_ascii_lowercase())
+                .or_default()
+                .entry(key.to_owned())
+                .or_default()
+                .push(Usage {
+                    bundle: bundle.to_owned(),
+                    script: script.to_owned(),
+                    name: name.to_owned(),
+                    field,
+                });
+        }
+    }
+
+    /// Appends the usages of `other`, e.g. of a bundle scanned in parallel.
+    pub fn merge(&mut self, other: LocalisedIndex) {
+        for (sheet, keys) in other.sheets {
+            let sheet = self.sheets.entry(sheet).or_default();
+            for (key, usages) in keys {
+                sheet.entry(key).or_default().extend(usages);
+            }
+        }
+    }
+
+    /// Keys of `language` which aren't referenced by any scanned object, by sheet.
+    pub fn orphans(&self, language: &Language) -> IndexMap<String, Vec<String>> {
+        let mut orphans = IndexMap::new();
+        for (sheet, keys) in language {
+            let used = self.sheets.get(&sheet.to_ascii_lowercase());
+            let unused: Vec<String> = keys
+                .keys()
+                .filter(|key| used.is_none_or(|used| !used.contains_key(*key)))
+                .cloned()
+                .collect();
+            if !unused.is_empty() {
+                orphans.insert(sheet.clone(), unused);
+            }
+        }
+        orphans
+    }
+
+    /// Referenced sheets and keys which don't exist in `language`.
+    pub fn unresolved(&self, language: &Language) -> Vec<(&str, &str)> {
+        self.sheets
+            .iter()
+            .flat_map(|(sheet, keys)| keys.keys().map(move |key| (sheet.as_str(), key.as_str())))
+            .filter(|(sheet, key)| {
+                language
+                    .get(*sheet)
+                    .is_none_or(|keys| !keys.contains_key(*key))
+            })
+            .collect()
+    }
+
+    pub fn usages(&self, sheet: &str, key: &str) -> &[Usage] {
+        self.sheets
+            .get(&sheet.to_ascii_lowercase())
+            .and_then(|keys| keys.get(key))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Every non-empty `LocalisedString` in `value` as field path, sheet and key.
+fn collect_localised_strings(value: &Value) -> Vec<(String, &str, &str)> {
+    let mut strings = Vec::new();
+    typetree::visit(value, &mut |path, value| {
+        let Some((sheet, key)) = typetree::as_localised(value) else {
+            return true;
+        };
+        if !sheet.is_empty() && !key.is_empty() {
+            strings.push((path.to_owned(), sheet, key));
+        }
+        false
+    });
+    strings
+}
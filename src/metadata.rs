@@ -0,0 +1,93 @@
+//! Which game build a dump came from, written as `metadata.json` next to every dump.
+
+use std::path::Path;
+
+use anyhow::Result;
+use rabex_env::Environment;
+use rabex_env::unity::types::{BuildSettings, PlayerSettings};
+use serde::{Deserialize, Serialize};
+
+use crate::lang::GAME_MANAGERS;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct GameMetadata {
+    pub company: String,
+    pub product: String,
+    /// The `bundleVersion` of the player settings, e.g. `1.0.28324`.
+    pub version: String,
+    pub unityVersion: String,
+    pub steamAppId: Option<u32>,
+    pub steamBuildId: Option<u64>,
+    /// Version of this tool.
+    pub dumperVersion: String,
+}
+
+/// The Steam app a game was found as, see [`crate::find_steam_game`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SteamApp {
+    pub app_id: u32,
+    /// Build ID of the app manifest, missing for some manually copied installs.
+    pub build_id: Option<u64>,
+}
+
+impl GameMetadata {
+    /// Reads the versions from `globalgamemanagers` and `app.info`, and the IDs from the Steam app the game was found as.
+    ///
+    /// Missing information is left empty, so dumps of modified or non-Steam installs still work.
+    pub fn read(env: &Environment, steam_app: Option<SteamApp>) -> Result<GameMetadata> {
+        let mut metadata = GameMetadata {
+            dumperVersion: env!("CARGO_PKG_VERSION").to_owned(),
+            ..Default::default()
+        };
+
+        let game_dir = &env.game_files.game_dir;
+        if let Ok(app_info) = std::fs::read_to_string(game_dir.join("app.info")) {
+            (metadata.company, metadata.product) = parse_app_info(&app_info);
+        }
+
+        let ggm = env.load_cached(GAME_MANAGERS)?;
+        if let Some(build_settings) = ggm.find_object_of::<BuildSettings>()? {
+            metadata.unityVersion = build_settings.m_Version;
+        }
+        if let Some(player_settings) = ggm.find_object_of::<PlayerSettings>()? {
+            metadata.version = player_settings.bundleVersion;
+            if metadata.product.is_empty() {
+                metadata.product = player_settings.productName;
+            }
+        }
+
+        if let Some(steam_app) = steam_app {
+            metadata.steamAppId = Some(steam_app.app_id);
+            metadata.steamBuildId = steam_app.build_id;
+        }
+
+        Ok(metadata)
+    }
+
+    /// Short description like `1.0.28324 (build 19911222)`, e.g. for table columns.
+    pub fn version_string(&self) -> String {
+        match self.steamBuildId {
+            Some(build_id) => format!("{} (build {build_id})", self.version),
+            None => self.version.clone(),
+        }
+    }
+
+    /// Writes `metadata.json` into `out`.
+    pub fn write(&self, out: &Path) -> Result<()> {
+        std::fs::create_dir_all(out)?;
+        std::fs::write(
+            out.join("metadata.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Company and product name from the two lines of `app.info`.
+pub fn parse_app_info(app_info: &str) -> (String, String) {
+    let mut lines = app_info.lines().map(str::trim);
+    let company = lines.next().unwrap_or_default().to_owned();
+    let product = lines.next().unwrap_or_default().to_owned();
+    (company, product)
+}
@@ -0,0 +1,31 @@
+//! Running independent dump steps in parallel.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+
+pub type Task<'a> = (&'a str, Box<dyn Fn() -> Result<()> + Send + Sync + 'a>);
+
+/// Runs all tasks in parallel and prints how long each one took, in the order of `tasks`.
+///
+/// Returns the first error in task order, after all tasks have finished.
+pub fn run_timed(tasks: &[Task]) -> Result<()> {
+    let start = Instant::now();
+    let results: Vec<(Duration, Result<()>)> = tasks
+        .par_iter()
+        .map(|(name, task)| {
+            let start = Instant::now();
+            let result = task().with_context(|| format!("Dumping {name}"));
+            (start.elapsed(), result)
+        })
+        .collect();
+
+    for ((name, _), (elapsed, result)) in tasks.iter().zip(&results) {
+        let status = if result.is_ok() { "" } else { " (failed)" };
+        eprintln!("{name:<24} {:>8.2}s{status}", elapsed.as_secs_f32());
+    }
+    eprintln!("{:<24} {:>8.2}s", "total", start.elapsed().as_secs_f32());
+
+    results.into_iter().try_for_each(|(_, result)| result)
+}
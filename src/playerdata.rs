@@ -0,0 +1,135 @@
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+
+use crate::fsm::Fsm;
+use crate::generated::{PlayerDataTest, Quest, ShopItem, ToolItemBasic};
+
+#[derive(Debug, Default)]
+pub struct FieldAccesses {
+    pub read_by: IndexSet<String>,
+    pub written_by: IndexSet<String>,
+}
+
+/// Index of every PlayerData field referenced by name, with the objects reading and writing it.
+#[derive(Debug, Default)]
+pub struct PlayerDataIndex {
+    pub fields: IndexMap<String, FieldAccesses>,
+}
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct PlayerDataFieldData {
+    pub field: String,
+    pub readBy: String,
+    pub writtenBy: String,
+}
+
+impl PlayerDataIndex {
+    pub fn read(&mut self, field: &str, source: &str) {
+        if field.is_empty() {
+            return;
+        }
+        let accesses = self.fields.entry(field.to_owned()).or_default();
+        accesses.read_by.insert(source.to_owned());
+    }
+
+    pub fn write(&mut self, field: &str, source: &str) {
+        if field.is_empty() {
+            return;
+        }
+        let accesses = self.fields.entry(field.to_owned()).or_default();
+        accesses.written_by.insert(source.to_owned());
+    }
+
+    pub fn add_test(&mut self, test: &PlayerDataTest, source: &str) {
+        for group in &test.TestGroups {
+            for test in &group.Tests {
+                self.read(&test.FieldName, source);
+            }
+        }
+    }
+
+    pub fn add_quest(&mut self, quest: &Quest) {
+        let source = format!("Quest {}", quest.m_Name);
+        self.add_test(&quest.playerDataTest, &source);
+        self.add_test(&quest.getTargetCondition, &source);
+        for target in &quest.targets {
+            self.add_test(&target.AltTest, &source);
+        }
+    }
+
+    pub fn add_shop_item(&mut self, item: &ShopItem) {
+        let source = format!("ShopItem {}", item.m_Name);
+        self.add_test(&item.extraAppearConditions, &source);
+        for sub_item in &item.subItems {
+            self.add_test(&sub_item.Condition, &source);
+        }
+        for spawn in &item.spawnOnPurchaseConditionals {
+            self.add_test(&spawn.Condition, &source);
+        }
+        self.write(&item.playerDataBoolName, &source);
+        self.write(&item.playerDataIntName, &source);
+        for field in &item.setExtraPlayerDataBools {
+            self.write(field, &source);
+        }
+        for operation in &item.setExtraPlayerDataInts {
+            self.write(&operation.variableName, &source);
+        }
+    }
+
+    pub fn add_tool(&mut self, tool: &ToolItemBasic) {
+        let source = format!("ToolItem {}", tool.m_Name);
+        self.add_test(&tool.alternateUnlockedTest, &source);
+    }
+
+    /// Adds the field names of `PlayerData` actions, e.g. `GetPlayerDataInt` or `SetPlayerDataBool`.
+    ///
+    /// Actions starting with `Set`, `Increment`, `Decrement` or `Add` are considered writes, everything else a read.
+    pub fn add_fsm(&mut self, fsm: &Fsm, source: &str) {
+        let source = format!("FSM {source}/{}-{}", fsm.gameObject, fsm.name);
+        for state in &fsm.states {
+            for action in &state.actions {
+                if !action.r#type.contains("PlayerData") {
+                    continue;
+                }
+                let is_write = ["Set", "Increment", "Decrement", "Add"]
+                    .iter()
+                    .any(|prefix| action.r#type.starts_with(prefix));
+
+                for param in action.params.values() {
+                    // FsmString parameters
+                    let Some(field) = param.get("value").and_then(|value| value.as_str()) else {
+                        continue;
+                    };
+                    match is_write {
+                        true => self.write(field, &source),
+                        false => self.read(field, &source),
+                    }
+                }
+            }
+        }
+    }
+
+    /// One row per field, sorted by name.
+    pub fn table(&self) -> Vec<PlayerDataFieldData> {
+        let mut rows: Vec<_> = self
+            .fields
+            .iter()
+            .map(|(field, accesses)| PlayerDataFieldData {
+                field: field.clone(),
+                readBy: join(&accesses.read_by),
+                writtenBy: join(&accesses.written_by),
+            })
+            .collect();
+        rows.sort_by(|a, b| a.field.cmp(&b.field));
+        rows
+    }
+}
+
+fn join(sources: &IndexSet<String>) -> String {
+    sources
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
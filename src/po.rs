@@ -0,0 +1,157 @@
+//! Minimal reader and writer for gettext `.po` files.
+//!
+//! Only `msgctxt`, `msgid` and `msgstr` are supported, plural forms and comments are skipped.
+
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PoEntry {
+    pub context: Option<String>,
+    pub id: String,
+    pub str: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PoFile {
+    /// The `Language` header field.
+    pub language: Option<String>,
+    pub entries: Vec<PoEntry>,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Context,
+    Id,
+    Str,
+    Other,
+}
+
+impl PoFile {
+    pub fn parse(text: &str) -> Result<PoFile> {
+        let mut entries = Vec::new();
+        let mut entry = PoEntry::default();
+        let mut field = None;
+
+        let finish = |entry: &mut PoEntry, entries: &mut Vec<PoEntry>| {
+            let entry = std::mem::take(entry);
+            if !entry.id.is_empty() || entry.context.is_some() || !entry.str.is_empty() {
+                entries.push(entry);
+            }
+        };
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                finish(&mut entry, &mut entries);
+                field = None;
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = match line.starts_with('"') {
+                true => (None, line),
+                false => {
+                    let (keyword, rest) =
+                        line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                    (Some(keyword), rest.trim())
+                }
+            };
+            if let Some(keyword) = keyword {
+                let new_field = match keyword {
+                    "msgctxt" => Field::Context,
+                    "msgid" => Field::Id,
+                    "msgstr" | "msgstr[0]" => Field::Str,
+                    _ => Field::Other,
+                };
+                // a new entry starts without a blank line in between
+                if matches!(new_field, Field::Context)
+                    || matches!((field, new_field), (Some(Field::Str), Field::Id))
+                {
+                    finish(&mut entry, &mut entries);
+                }
+                field = Some(new_field);
+            }
+
+            let value = unquote(rest).ok_or_else(|| {
+                anyhow::anyhow!("line {}: expected quoted string, got '{line}'", i + 1)
+            })?;
+            match field {
+                Some(Field::Context) => entry.context.get_or_insert_default().push_str(&value),
+                Some(Field::Id) => entry.id.push_str(&value),
+                Some(Field::Str) => entry.str.push_str(&value),
+                Some(Field::Other) => {}
+                None => bail!("line {}: string without keyword", i + 1),
+            }
+        }
+        finish(&mut entry, &mut entries);
+
+        let mut language = None;
+        if let Some(header) = entries.first().filter(|entry| entry.id.is_empty()) {
+            language = header
+                .str
+                .lines()
+                .find_map(|line| line.strip_prefix("Language:"))
+                .map(|language| language.trim().to_owned())
+                .filter(|language| !language.is_empty());
+            entries.remove(0);
+        }
+
+        Ok(PoFile { language, entries })
+    }
+
+    pub fn write(&self) -> String {
+        let mut out = String::new();
+        out.push_str("msgid \"\"\nmsgstr \"\"\n");
+        out.push_str("\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+        if let Some(language) = &self.language {
+            writeln!(out, "\"Language: {}\\n\"", escape(language)).unwrap();
+        }
+        for entry in &self.entries {
+            out.push('\n');
+            if let Some(context) = &entry.context {
+                writeln!(out, "msgctxt \"{}\"", escape(context)).unwrap();
+            }
+            writeln!(out, "msgid \"{}\"", escape(&entry.id)).unwrap();
+            writeln!(out, "msgstr \"{}\"", escape(&entry.str)).unwrap();
+        }
+        out
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let inner = s.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
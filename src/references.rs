@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use rabex_env::handle::SerializedFileHandle;
+use rabex_env::rabex::objects::{ClassId, PPtr};
+use rabex_env::unity::types::MonoScript;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cache::CachedBundle;
+use crate::generated::IntReference;
+use crate::typetree;
+
+/// Reverse index from `IntReference`s (`CostReference`, `DamageReference`) to every object pointing to them.
+#[derive(Debug, Default, Serialize)]
+pub struct ReferenceIndex {
+    /// By `<script>/<name>`, as references of different scripts may share a name.
+    pub references: IndexMap<String, ReferenceUsages>,
+    /// Key of every registered reference by its identity, to find it from pointers in other bundles.
+    #[serde(skip)]
+    targets: HashMap<ReferenceTarget, String>,
+    /// Path IDs of all `targets`, to skip resolving pointers which can't point to a reference.
+    #[serde(skip)]
+    path_ids: HashSet<i64>,
+}
+
+/// Identity of a reference as seen through a pointer.
+///
+/// Path IDs are only unique within a file and which file a pointer's `m_FileID` resolves to isn't known
+/// outside of rabex, so the object is identified together with its script and name, read by dereferencing
+/// the pointer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReferenceTarget {
+    pub path_id: i64,
+    /// Path ID of the `m_Script` of the reference.
+    pub script: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct ReferenceUsages {
+    pub script: String,
+    pub name: String,
+    pub value: i32,
+    pub usedBy: Vec<Usage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Usage {
+    pub bundle: String,
+    pub script: String,
+    pub name: String,
+    /// Path of the field containing the reference, e.g. `subItems[1].costReference`.
+    pub field: String,
+}
+
+impl ReferenceIndex {
+    /// Registers all references with the given script in `bundle`, so they're listed even when unused.
+    pub fn add_references(&mut self, bundle: &CachedBundle, script: &str) -> Result<()> {
+        for object in bundle
+            .objects
+            .iter()
+            .filter(|object| object.script == script)
+        {
+            let reference = IntReference::deserialize(&object.value)?;
+            self.add_reference(script, object.path_id, reference);
+        }
+        Ok(())
+    }
+
+    pub fn add_reference(&mut self, script: &str, path_id: i64, reference: IntReference) {
+        let key = format!("{script}/{}", reference.m_Name);
+        let target = ReferenceTarget {
+            path_id,
+            script: reference.m_Script.m_PathID,
+            name: reference.m_Name.clone(),
+        };
+        self.targets.insert(target, key.clone());
+        self.path_ids.insert(path_id);
+        self.references.insert(
+            key,
+            ReferenceUsages {
+                script: script.to_owned(),
+                name: reference.m_Name,
+                value: reference.value,
+                usedBy: Vec::new(),
+            },
+        );
+    }
+
+    /// A copy of the registered references without usages, to scan bundles in parallel
+    /// and [`merge`](Self::merge) the results afterwards.
+    pub fn without_usages(&self) -> ReferenceIndex {
+        ReferenceIndex {
+            references: self
+                .references
+                .iter()
+                .map(|(key, usages)| {
+                    let usages = ReferenceUsages {
+                        script: usages.script.clone(),
+                        name: usages.name.clone(),
+                        value: usages.value,
+                        usedBy: Vec::new(),
+                    };
+                    (key.clone(), usages)
+                })
+                .collect(),
+            targets: self.targets.clone(),
+            path_ids: self.path_ids.clone(),
+        }
+    }
+
+    /// Appends the usages of `other`.
+    pub fn merge(&mut self, other: ReferenceIndex) {
+        for (key, usages) in other.references {
+            self.references
+                .entry(key)
+                .or_insert_with(|| ReferenceUsages {
+                    script: usages.script,
+                    name: usages.name,
+                    value: usages.value,
+                    usedBy: Vec::new(),
+                })
+                .usedBy
+                .extend(usages.usedBy);
+        }
+        self.targets.extend(other.targets);
+        self.path_ids.extend(other.path_ids);
+    }
+
+    /// Scans every MonoBehaviour of the bundle named `name` for pointers to registered references.
+    pub fn add_usages(&mut self, name: &str, bundle: &CachedBundle) {
+        let mut resolve = bundle.target_resolver();
+        for object in &bundle.objects {
+            self.add_value(name, &object.script, &object.value, &mut resolve);
+        }
+    }
+
+    /// Adds the usages of an object read as [`Value`].
+    ///
+    /// `resolve` dereferences a pointer of the object's file to the [`ReferenceTarget`] it points to, if any.
+    pub fn add_value(
+        &mut self,
+        bundle: &str,
+        script: &str,
+        value: &Value,
+        resolve: &mut impl FnMut(PPtr) -> Option<ReferenceTarget>,
+    ) {
+        let pptrs = typetree::object_pptrs(value);
+
+        let name = value["m_Name"].as_str().unwrap_or_default();
+        for (field, pptr) in pptrs {
+            if !self.path_ids.contains(&pptr.m_PathID) {
+                continue;
+            }
+            let Some(key) = resolve(pptr).and_then(|target| self.targets.get(&target)) else {
+                continue;
+            };
+            self.references[key].usedBy.push(Usage {
+                bundle: bundle.to_owned(),
+                script: script.to_owned(),
+                name: name.to_owned(),
+                field,
+            });
+        }
+    }
+}
+
+/// Class name of the `m_Script` of a MonoBehaviour read as [`Value`], cached by script pointer.
+pub(crate) fn script_name(
+    file: SerializedFileHandle,
+    value: &Value,
+    cache: &mut HashMap<(i32, i64), String>,
+) -> String {
+    match value.get("m_Script").map(PPtr::deserialize) {
+        Some(Ok(script)) => cache
+            .entry((script.m_FileID, script.m_PathID))
+            .or_insert_with(|| {
+                file.deref_read_optional(script.typed::<MonoScript>())
+                    .ok()
+                    .flatten()
+                    .map(|script| script.m_ClassName)
+                    .unwrap_or_default()
+            })
+            .clone(),
+        _ => String::new(),
+    }
+}
+
+/// The object behind `pptr`, if it is shaped like an [`IntReference`].
+pub fn reference_target(file: SerializedFileHandle, pptr: PPtr) -> Option<ReferenceTarget> {
+    let object = file.deref(pptr.typed::<IntReference>()).ok()?;
+    if object.class_id() != ClassId::MonoBehaviour {
+        return None;
+    }
+    let reference = object.read().ok()?;
+    Some(ReferenceTarget {
+        path_id: pptr.m_PathID,
+        script: reference.m_Script.m_PathID,
+        name: reference.m_Name,
+    })
+}
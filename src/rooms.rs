@@ -0,0 +1,101 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+use indexmap::IndexSet;
+use serde::Serialize;
+
+use crate::dot::quote;
+use crate::scenes::{ObjectKind, Scene};
+
+#[derive(Debug, Clone, Serialize)]
+#[allow(non_snake_case)]
+pub struct Transition {
+    pub scene: String,
+    pub gate: String,
+    pub targetScene: String,
+    pub targetGate: String,
+}
+
+/// Graph of all scenes, connected by their `TransitionPoint`s.
+#[derive(Debug, Default, Serialize)]
+pub struct RoomGraph {
+    pub scenes: IndexSet<String>,
+    pub transitions: Vec<Transition>,
+}
+
+impl RoomGraph {
+    pub fn add_scene(&mut self, scene: &Scene) {
+        self.scenes.insert(scene.name.clone());
+        for object in scene.objects_of(ObjectKind::Transition) {
+            let target_scene = object.data["targetScene"].as_str().unwrap_or_default();
+            if target_scene.is_empty() {
+                continue;
+            }
+            let gate = object.path.rsplit('/').next().unwrap_or_default();
+            self.transitions.push(Transition {
+                scene: scene.name.clone(),
+                gate: gate.to_owned(),
+                targetScene: target_scene.to_owned(),
+                targetGate: object.data["entryPoint"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+            });
+        }
+    }
+
+    pub fn transitions_from<'a>(&'a self, scene: &str) -> impl Iterator<Item = &'a Transition> {
+        self.transitions.iter().filter(move |t| t.scene == scene)
+    }
+
+    /// Finds the shortest sequence of transitions leading from `from` to `to`.
+    ///
+    /// Returns an empty path if both scenes are the same, and `None` if `to` is unreachable.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<&Transition>> {
+        let mut came_from: HashMap<&str, &Transition> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(scene) = queue.pop_front() {
+            if scene == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while current != from {
+                    let transition = came_from[current];
+                    path.push(transition);
+                    current = &transition.scene;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for transition in self.transitions_from(scene) {
+                let target = transition.targetScene.as_str();
+                if target != from && !came_from.contains_key(target) {
+                    came_from.insert(target, transition);
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph rooms {\n");
+        for scene in &self.scenes {
+            writeln!(dot, "    {};", quote(scene)).unwrap();
+        }
+        for t in &self.transitions {
+            writeln!(
+                dot,
+                "    {} -> {} [label={}];",
+                quote(&t.scene),
+                quote(&t.targetScene),
+                quote(&format!("{} -> {}", t.gate, t.targetGate))
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
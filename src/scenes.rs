@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rabex_env::handle::{ScriptFilter, ScriptFilterContains, SerializedFileHandle};
+use rabex_env::rabex::objects::{ClassId, PPtr};
+use rabex_env::unity::types::Transform;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::generated::SavedItem;
+use crate::references::script_name;
+use crate::typetree;
+
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct Scene {
+    pub name: String,
+    pub rootObjects: Vec<String>,
+    pub objects: Vec<SceneObject>,
+}
+
+/// A component of interest, together with the GameObject it is attached to.
+#[derive(Debug, Serialize)]
+#[allow(non_snake_case)]
+pub struct SceneObject {
+    /// Path of the GameObject in the hierarchy, e.g. `_Transitions/right1`.
+    pub path: String,
+    pub kind: ObjectKind,
+    /// Class name of the component's script.
+    pub script: String,
+    /// `itemData.ID` as referenced by `PersistentBoolTest`s, for persistent items.
+    pub persistentId: Option<String>,
+    /// Name of the `SavedItem` given by pickups.
+    pub item: Option<String>,
+    /// Names of the objects a shop points to, i.e. its `ShopItem`s or the item lists containing them.
+    pub stock: Vec<String>,
+    pub data: Value,
+}
+
+impl SceneObject {
+    /// Places a component of the given kind in the hierarchy, by the GameObject it is attached to.
+    ///
+    /// `item` and `stock` are left empty, as they reference other objects in the file.
+    pub fn new(
+        kind: ObjectKind,
+        script: String,
+        data: Value,
+        hierarchy: &Hierarchy,
+    ) -> SceneObject {
+        let path = match data
+            .get("m_GameObject")
+            .and_then(|go| go["m_PathID"].as_i64())
+        {
+            Some(go) => hierarchy.path(go),
+            None => String::new(),
+        };
+        let persistent_id = match kind {
+            ObjectKind::PersistentBool => PersistentBoolItem::deserialize(&data)
+                .ok()
+                .map(|item| item.itemData.ID),
+            _ => None,
+        };
+        SceneObject {
+            path,
+            kind,
+            script,
+            persistentId: persistent_id,
+            item: None,
+            stock: Vec::new(),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ObjectKind {
+    PersistentBool,
+    Transition,
+    Bench,
+    Shop,
+    Pickup,
+    /// The board relics are turned in at, paying their reward.
+    RelicBoard,
+}
+
+fn components() -> [(ObjectKind, &'static dyn ScriptFilter); 6] {
+    [
+        (ObjectKind::PersistentBool, &"PersistentBoolItem"),
+        (ObjectKind::Transition, &"TransitionPoint"),
+        (ObjectKind::Bench, &ScriptFilterContains("RestBench")),
+        (ObjectKind::Shop, &ScriptFilterContains("ShopOwner")),
+        (ObjectKind::Pickup, &ScriptFilterContains("ItemPickup")),
+        (ObjectKind::RelicBoard, &ScriptFilterContains("RelicBoard")),
+    ]
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct PersistentBoolItem {
+    itemData: PersistentItemData,
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct PersistentItemData {
+    ID: String,
+}
+
+impl Scene {
+    pub fn read(name: &str, file: SerializedFileHandle) -> Result<Scene> {
+        let hierarchy = Hierarchy::read(file.reborrow())?;
+
+        let mut script_names = HashMap::new();
+        let mut objects = Vec::new();
+        for (kind, filter) in components() {
+            for component in file.scripts::<Value>(filter)? {
+                let data = component.read()?;
+                let script = script_name(file.reborrow(), &data, &mut script_names);
+                let item = match kind {
+                    ObjectKind::Pickup => pickup_item(file.reborrow(), &data)?,
+                    _ => None,
+                };
+                let stock = match kind {
+                    ObjectKind::Shop => referenced_names(file.reborrow(), &data),
+                    _ => Vec::new(),
+                };
+                objects.push(SceneObject {
+                    item,
+                    stock,
+                    ..SceneObject::new(kind, script, data, &hierarchy)
+                });
+            }
+        }
+
+        Ok(Scene {
+            name: name.to_owned(),
+            rootObjects: hierarchy.roots(),
+            objects,
+        })
+    }
+
+    pub fn objects_of(&self, kind: ObjectKind) -> impl Iterator<Item = &SceneObject> {
+        self.objects
+            .iter()
+            .filter(move |object| object.kind == kind)
+    }
+
+    /// The object a `PersistentBoolTest` with the given `ID` refers to.
+    pub fn find_persistent(&self, id: &str) -> Option<&SceneObject> {
+        self.objects_of(ObjectKind::PersistentBool)
+            .find(|object| object.persistentId.as_deref() == Some(id))
+    }
+}
+
+/// A `Transform` and the name of its GameObject, by path ID.
+#[derive(Debug, Clone)]
+pub struct HierarchyNode {
+    pub transform: i64,
+    pub game_object: i64,
+    /// Transform of the parent, `0` for root objects.
+    pub father: i64,
+    pub name: String,
+}
+
+/// GameObject names and parents, by GameObject path ID.
+pub struct Hierarchy {
+    names: HashMap<i64, String>,
+    parents: HashMap<i64, i64>,
+    roots: Vec<i64>,
+}
+
+impl Hierarchy {
+    pub fn read(file: SerializedFileHandle) -> Result<Hierarchy> {
+        let mut nodes = Vec::new();
+        for transform in file.objects_of::<Transform>()? {
+            let path_id = transform.path_id();
+            let transform = transform.read()?;
+            nodes.push(HierarchyNode {
+                transform: path_id,
+                game_object: transform.m_GameObject.m_PathID,
+                father: transform.m_Father.m_PathID,
+                name: file
+                    .deref_read_optional(transform.m_GameObject)?
+                    .map(|object| object.m_Name)
+                    .unwrap_or_default(),
+            });
+        }
+        Ok(Hierarchy::new(&nodes))
+    }
+
+    pub fn new(nodes: &[HierarchyNode]) -> Hierarchy {
+        let go_of_transform: HashMap<_, _> = nodes
+            .iter()
+            .map(|node| (node.transform, node.game_object))
+            .collect();
+
+        let mut hierarchy = Hierarchy {
+            names: HashMap::new(),
+            parents: HashMap::new(),
+            roots: Vec::new(),
+        };
+        for node in nodes {
+            hierarchy.names.insert(node.game_object, node.name.clone());
+            match go_of_transform.get(&node.father) {
+                Some(&parent) if node.father != 0 => {
+                    hierarchy.parents.insert(node.game_object, parent);
+                }
+                _ => hierarchy.roots.push(node.game_object),
+            }
+        }
+        hierarchy
+    }
+
+    /// Path of the GameObject, e.g. `_Transitions/right1`.
+    pub fn path(&self, mut go: i64) -> String {
+        let mut segments = vec![self.names.get(&go).map(String::as_str).unwrap_or_default()];
+        while let Some(&parent) = self.parents.get(&go) {
+            segments.push(
+                self.names
+                    .get(&parent)
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+            );
+            go = parent;
+        }
+        segments.reverse();
+        segments.join("/")
+    }
+
+    pub fn roots(&self) -> Vec<String> {
+        self.roots
+            .iter()
+            .map(|&go| self.names[&go].clone())
+            .collect()
+    }
+}
+
+fn pickup_item(file: SerializedFileHandle, data: &Value) -> Result<Option<String>> {
+    let Some(Ok(item)) = data.get("item").map(PPtr::deserialize) else {
+        return Ok(None);
+    };
+    let item = file.deref_read_optional(item.typed::<SavedItem>())?;
+    Ok(item.map(|item| item.m_Name))
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct Named {
+    m_Name: String,
+}
+
+/// Names of the MonoBehaviours `data` points to.
+fn referenced_names(file: SerializedFileHandle, data: &Value) -> Vec<String> {
+    typetree::object_pptrs(data)
+        .into_iter()
+        .filter_map(|(_, pptr)| {
+            let object = file.deref(pptr.typed::<Named>()).ok()?;
+            if object.class_id() != ClassId::MonoBehaviour {
+                return None;
+            }
+            object.read().ok().map(|named| named.m_Name)
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+
+use crate::lang::Languages;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub lang: String,
+    pub sheet: String,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SearchOptions {
+    /// Only search this language.
+    pub lang: Option<String>,
+    /// Match every word of the query in any order, ignoring markup and punctuation.
+    pub fuzzy: bool,
+}
+
+/// Case-insensitive search through the values of all language sheets.
+pub fn search(languages: &Languages, query: &str, options: &SearchOptions) -> Vec<SearchMatch> {
+    let query = match options.fuzzy {
+        true => normalize(query),
+        false => query.to_lowercase(),
+    };
+    let words: Vec<&str> = query.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    for (lang, language) in languages {
+        if options.lang.as_ref().is_some_and(|only| only != lang) {
+            continue;
+        }
+        for (sheet, keys) in language {
+            for (key, value) in keys {
+                let is_match = match options.fuzzy {
+                    true => {
+                        let value = normalize(value);
+                        words.iter().all(|word| value.contains(word))
+                    }
+                    false => value.to_lowercase().contains(&query),
+                };
+                if is_match {
+                    matches.push(SearchMatch {
+                        lang: lang.clone(),
+                        sheet: sheet.clone(),
+                        key: key.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+/// Lowercases and replaces markup tags and punctuation with spaces.
+fn normalize(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut in_tag = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' if chars.peek().is_some_and(|&c| c.is_alphabetic() || c == '/') => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if in_tag => {}
+            c if c.is_alphanumeric() => out.extend(c.to_lowercase()),
+            _ => out.push(' '),
+        }
+    }
+    out
+}
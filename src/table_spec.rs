@@ -0,0 +1,210 @@
+//! Tables defined in a JSON file instead of code, evaluated on objects read through their type tree.
+//!
+//! ```json
+//! {
+//!   "tables": [{
+//!     "name": "tools",
+//!     "bundle": "dataassets_assets_assets/dataassets/tools/toolitems.bundle",
+//!     "script": "Tool",
+//!     "contains": true,
+//!     "columns": [
+//!       { "name": "name", "path": "displayName", "localised": true },
+//!       { "name": "silkRequired", "path": "usageOptions.SilkRequired" },
+//!       { "name": "cost", "path": "costReference", "deref": "value" }
+//!     ]
+//!   }]
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use rabex_env::Environment;
+use rabex_env::handle::{ScriptFilter, ScriptFilterContains};
+use rabex_env::rabex::objects::PPtr;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::lang::Language;
+use crate::tables::TableWriter;
+use crate::warning::Warning;
+
+#[derive(Debug, Deserialize)]
+pub struct TableSpecs {
+    pub tables: Vec<TableSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TableSpec {
+    /// Name of the CSV file, without extension.
+    pub name: String,
+    /// Bundle path, as accepted by `load_addressables_bundle_content`.
+    pub bundle: String,
+    /// Script class name of the MonoBehaviours to include.
+    pub script: String,
+    /// Match every script containing `script` instead of only the exact name.
+    #[serde(default)]
+    pub contains: bool,
+    pub columns: Vec<ColumnSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ColumnSpec {
+    pub name: String,
+    /// Field path like `usageOptions.SilkRequired` or `targets[0].Count`.
+    pub path: String,
+    /// The field is a `LocalisedString` which is resolved to its text.
+    #[serde(default)]
+    pub localised: bool,
+    /// The field is a `PPtr`, and this is the field path to read from the object it points to.
+    #[serde(default)]
+    pub deref: Option<String>,
+}
+
+impl TableSpecs {
+    pub fn read(path: &Path) -> Result<TableSpecs> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&data)
+            .with_context(|| format!("invalid table spec {}", path.display()))
+    }
+}
+
+impl TableSpec {
+    pub fn header(&self) -> Vec<&str> {
+        self.columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect()
+    }
+
+    /// Evaluates all columns on an object. `deref` reads the object behind a `PPtr`.
+    ///
+    /// Cells which can't be evaluated, e.g. because of a missing field, are left empty and added to `warnings`.
+    pub fn row(
+        &self,
+        value: &Value,
+        lang: &Language,
+        deref: &mut impl FnMut(PPtr) -> Result<Value>,
+        warnings: &mut Vec<Warning>,
+    ) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| {
+                column.cell(value, lang, deref).unwrap_or_else(|e| {
+                    let object = value["m_Name"].as_str().unwrap_or_default();
+                    warnings.push(Warning::new(
+                        object,
+                        format!("table '{}', column '{}': {e:#}", self.name, column.name),
+                    ));
+                    String::new()
+                })
+            })
+            .collect()
+    }
+
+    /// Writes `out/<name>.csv`, with a `gameVersion` column if `version` is given, returning the warnings of all rows.
+    pub fn dump(
+        &self,
+        env: &Environment,
+        out: &Path,
+        lang: &Language,
+        version: Option<&str>,
+    ) -> Result<Vec<Warning>> {
+        let file = env.load_addressables_bundle_content(&self.bundle)?;
+        let filter: &dyn ScriptFilter = match self.contains {
+            true => &ScriptFilterContains(&self.script),
+            false => &self.script.as_str(),
+        };
+
+        let mut cache: HashMap<(i32, i64), Value> = HashMap::new();
+        let mut deref = |pptr: PPtr| -> Result<Value> {
+            if let Some(value) = cache.get(&(pptr.m_FileID, pptr.m_PathID)) {
+                return Ok(value.clone());
+            }
+            let value = file.deref(pptr.typed::<Value>())?.read()?;
+            cache.insert((pptr.m_FileID, pptr.m_PathID), value.clone());
+            Ok(value)
+        };
+
+        let mut warnings = Vec::new();
+        let path = out.join(&self.name).with_extension("csv");
+        let mut writer = TableWriter::create(&path, version)?;
+        writer.write_header(self.header())?;
+        for object in file.scripts::<Value>(filter)? {
+            let value = object.read()?;
+            writer.write_row(self.row(&value, lang, &mut deref, &mut warnings))?;
+        }
+        writer.into_inner()?;
+
+        Ok(warnings)
+    }
+}
+
+impl ColumnSpec {
+    fn cell(
+        &self,
+        value: &Value,
+        lang: &Language,
+        deref: &mut impl FnMut(PPtr) -> Result<Value>,
+    ) -> Result<String> {
+        let Some(field) = lookup(value, &self.path) else {
+            bail!("no field '{}'", self.path);
+        };
+
+        if let Some(target_path) = &self.deref {
+            let pptr = PPtr::deserialize(field)
+                .with_context(|| format!("'{}' is not a PPtr", self.path))?;
+            if pptr.m_PathID == 0 {
+                return Ok(String::new());
+            }
+            let target = deref(pptr)?;
+            let target_field = lookup(&target, target_path)
+                .with_context(|| format!("no field '{target_path}' in dereferenced object"))?;
+            return Ok(cell_text(target_field));
+        }
+
+        if self.localised {
+            let sheet = field["Sheet"].as_str().unwrap_or_default();
+            let key = field["Key"].as_str().unwrap_or_default();
+            if sheet.is_empty() && key.is_empty() {
+                return Ok(String::new());
+            }
+            return lang
+                .get(&sheet.to_ascii_lowercase())
+                .and_then(|keys| keys.get(key))
+                .cloned()
+                .with_context(|| format!("unknown localised string {sheet}/{key}"));
+        }
+
+        Ok(cell_text(field))
+    }
+}
+
+/// Looks up a field path like `usageOptions.SilkRequired` or `targets[0].Count`.
+pub fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (field, indices) = segment.split_once('[').unwrap_or((segment, ""));
+        if !field.is_empty() {
+            current = current.get(field)?;
+        }
+        for index in indices.split('[') {
+            let Some(index) = index.strip_suffix(']') else {
+                continue;
+            };
+            current = current.get(index.parse::<usize>().ok()?)?;
+        }
+    }
+    Some(current)
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        // numbers, booleans, and arrays and objects as JSON
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,347 @@
+//! Rows of the CSV tables written by `dump_silksong_data`.
+//!
+//! The constructors take the deserialized object together with everything it references,
+//! so the mapping can be done without access to the game files.
+#![allow(non_snake_case)]
+
+use std::fmt::Write;
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::enums::*;
+use crate::generated::*;
+use crate::lang::Language;
+use crate::warning::Warning;
+
+/// Writes a CSV table, with a `gameVersion` column appended to every row if a version is given.
+///
+/// The column tells rows apart after combining the tables of different game builds.
+pub struct TableWriter<W: std::io::Write> {
+    writer: csv::Writer<W>,
+    version: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GameVersion<'a> {
+    gameVersion: &'a str,
+}
+
+impl TableWriter<File> {
+    pub fn create(path: &Path, version: Option<&str>) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        Ok(TableWriter::new(file, version))
+    }
+}
+
+impl<W: std::io::Write> TableWriter<W> {
+    pub fn new(writer: W, version: Option<&str>) -> Self {
+        TableWriter {
+            writer: csv::Writer::from_writer(writer),
+            version: version.map(str::to_owned),
+        }
+    }
+
+    /// Writes a row serialized from a struct. The header is written before the first row.
+    pub fn serialize(&mut self, row: impl Serialize) -> Result<()> {
+        match &self.version {
+            Some(version) => self.writer.serialize((
+                row,
+                GameVersion {
+                    gameVersion: version,
+                },
+            ))?,
+            None => self.writer.serialize(row)?,
+        }
+        Ok(())
+    }
+
+    /// Writes the header of a table whose rows are written with [`TableWriter::write_row`].
+    pub fn write_header<'a>(&mut self, columns: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        let mut header: Vec<&str> = columns.into_iter().collect();
+        if self.version.is_some() {
+            header.push("gameVersion");
+        }
+        self.writer.write_record(header)?;
+        Ok(())
+    }
+
+    pub fn write_row(&mut self, mut row: Vec<String>) -> Result<()> {
+        if let Some(version) = &self.version {
+            row.push(version.clone());
+        }
+        self.writer.write_record(row)?;
+        Ok(())
+    }
+
+    /// Flushes the table and returns the underlying writer.
+    pub fn into_inner(self) -> Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|e| anyhow::anyhow!("failed to flush table: {}", e.error()))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnemyJournalRecordData {
+    pub name: String,
+    pub killsRequired: i32,
+    pub recordType: RecordTypes,
+    pub requiredType: RequiredTypes,
+}
+impl EnemyJournalRecordData {
+    pub fn new(item: EnemyJournalRecord, lang: &Language) -> Result<Self> {
+        Ok(EnemyJournalRecordData {
+            name: item.displayName.get(lang)?.to_owned(),
+            killsRequired: item.killsRequired,
+            recordType: item.recordType.try_into()?,
+            requiredType: item.requiredType.try_into()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectableItemData {
+    pub name: String,
+    pub displayName: String,
+    pub rewardAmount: i32,
+}
+impl CollectableItemData {
+    pub fn new(item: CollectableItemRelicType, lang: &Language) -> Result<Self> {
+        Ok(CollectableItemData {
+            displayName: item.typeName.get(lang)?.to_owned(),
+            name: item.m_Name,
+            rewardAmount: item.rewardAmount,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShopItemData {
+    pub name: String,
+    pub internalName: String,
+    pub cost: i32,
+    pub item: Option<String>,
+    pub conditions: Option<String>,
+    pub quest: Option<String>,
+}
+impl ShopItemData {
+    /// `quests` are the names of the quests in `questsAppearConditions`.
+    pub fn new(
+        item: ShopItem,
+        lang: &Language,
+        cost_ref: Option<CostReference>,
+        required_item: Option<CollectableItem>,
+        quests: &[String],
+    ) -> Result<Self> {
+        let conditions = if !item.extraAppearConditions.TestGroups.is_empty() {
+            Some(item.extraAppearConditions.to_string())
+        } else {
+            None
+        };
+
+        let quest_requirement = if !item.questsAppearConditions.is_empty() {
+            let mut quests_str = String::new();
+            for (quest_test, quest) in item.questsAppearConditions.iter().zip(quests) {
+                write!(&mut quests_str, "'{}'", quest)?;
+                let m = |b: u8| match b != 0 {
+                    true => "",
+                    false => "not ",
+                };
+                if quest_test.CheckAvailable != 0 {
+                    write!(&mut quests_str, " {}available", m(quest_test.IsAvailable),)?;
+                }
+                if quest_test.CheckAccepted != 0 {
+                    write!(&mut quests_str, " {}accepted", m(quest_test.IsAccepted),)?;
+                }
+                if quest_test.CheckCompletedAmount != 0 {
+                    write!(
+                        &mut quests_str,
+                        " completedamount {}",
+                        quest_test.CompletedAmount
+                    )?;
+                }
+                if quest_test.CheckCompletable != 0 {
+                    write!(
+                        &mut quests_str,
+                        " {}completable",
+                        m(quest_test.IsCompletable),
+                    )?;
+                }
+                if quest_test.CheckCompleted != 0 {
+                    write!(&mut quests_str, " {}completed", m(quest_test.IsCompleted),)?;
+                }
+                if quest_test.CheckWasEverCompleted != 0 {
+                    write!(
+                        &mut quests_str,
+                        " {}completed",
+                        m(quest_test.WasEverCompleted),
+                    )?;
+                }
+            }
+            Some(quests_str)
+        } else {
+            None
+        };
+
+        let display_name = item.displayName.get(lang)?.to_owned();
+        let internal_name = item.m_Name;
+
+        Ok(ShopItemData {
+            name: display_name,
+            internalName: internal_name,
+            cost: cost_ref.map(|cost| cost.value).unwrap_or(item.cost),
+            item: required_item.map(|item| item.m_Name),
+            conditions,
+            quest: quest_requirement,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DamageTagData {
+    pub name: String,
+    pub damageAmount: i32,
+    pub damageCooldownTimer: Option<String>,
+    pub delayPerHit: f32,
+    pub isToolDamage: u8,
+    pub nailElement: NailElement,
+    pub specialDamageType: SpecialDamageType,
+    pub startDelay: f32,
+    pub totalHitLimit: i32,
+}
+impl DamageTagData {
+    pub fn new(item: DamageTag, damage_cooldown: Option<TimerGroup>) -> Result<Self> {
+        Ok(DamageTagData {
+            name: item.m_Name,
+            damageAmount: item.damageAmount,
+            damageCooldownTimer: damage_cooldown.map(|cooldown| format!("{}s", cooldown.delay)),
+            delayPerHit: item.delayPerHit,
+            isToolDamage: item.isToolDamage,
+            nailElement: item.nailElement.try_into()?,
+            specialDamageType: item.specialDamageType.try_into()?,
+            startDelay: item.startDelay,
+            totalHitLimit: item.totalHitLimit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolItemData {
+    pub r#type: ToolItemType,
+    pub name: String,
+    pub damageFlags: ToolDamageFlags,
+    pub poisonDamageTicks: i32,
+    pub replenishResource: ReplenishResources,
+    pub replenishCost: Option<f32>,
+    #[serde(serialize_with = "serialize_num_bool")]
+    pub SilkRequired: i32,
+}
+impl ToolItemData {
+    /// `warnings` collects tools whose replenish cost can't be computed.
+    pub fn new(
+        item: ToolItemBasic,
+        display_name: Option<String>,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<Self> {
+        let replenishCost = item.replenish_cost(warnings)?;
+        Ok(ToolItemData {
+            name: display_name.unwrap_or(item.m_Name),
+            r#type: item.r#type.try_into()?,
+            damageFlags: item.damageFlags.try_into()?,
+            poisonDamageTicks: item.poisonDamageTicks,
+            replenishResource: item.replenishResource.try_into()?,
+            replenishCost,
+            SilkRequired: item.usageOptions.SilkRequired,
+        })
+    }
+}
+
+/// Name of a tool as shown in game, from the `displayName` of its `countKey`.
+///
+/// Tools which only count towards a ruined tool keep their internal name.
+pub fn tool_display_name(count_key: Option<SavedItem>, lang: &Language) -> Option<String> {
+    count_key
+        .and_then(|item| item.displayName)
+        .and_then(|name| name.try_get(lang).map(ToOwned::to_owned))
+        .filter(|name| *name != "Ruined Tool")
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestData {
+    pub name: String,
+    // getTargetCondition: String, empty
+    // persistentBoolTests: Vec<serde_json::Value>,
+    // questType: PPtr,
+    // require stuff
+    pub rewardItem: String,
+    pub rewardCount: i32,
+    pub rewardCountAct3: i32,
+    // targetCount: i32,
+    pub requirements: String,
+    pub condition: String,
+}
+impl QuestData {
+    /// `counters` are the names of the `Counter`s of each of the quest's `targets`.
+    pub fn new(
+        item: Quest,
+        lang: &Language,
+        reward: Option<SavedItem>,
+        counters: &[String],
+    ) -> Self {
+        let name = item
+            .invItemAppendDesc
+            .try_get(lang)
+            .map(ToOwned::to_owned)
+            .unwrap_or(item.m_Name);
+        let reward = reward.map(|x| x.m_Name).unwrap_or_default();
+
+        QuestData {
+            name,
+            // getTargetCondition: item.getTargetCondition.to_string(),
+            condition: item.playerDataTest.to_string(),
+            rewardCount: item.rewardCount,
+            rewardItem: reward,
+            rewardCountAct3: item.rewardCountAct3,
+            // targetCount: item.targetCount,
+            requirements: item
+                .targets
+                .iter()
+                .zip(counters)
+                // .map(|x| format!("{} {},", x.AltTest.to_string(), x.Count))
+                .map(|(x, item)| {
+                    if x.AltTest.TestGroups.is_empty() {
+                        format!("{} {} ", x.Count, item)
+                    } else {
+                        format!("{} ", x.AltTest)
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntReferenceData {
+    pub name: String,
+    pub value: i32,
+}
+impl From<IntReference> for IntReferenceData {
+    fn from(value: IntReference) -> Self {
+        IntReferenceData {
+            name: value.m_Name,
+            value: value.value,
+        }
+    }
+}
+
+fn serialize_num_bool<S: serde::Serializer>(val: &i32, s: S) -> Result<S::Ok, S::Error> {
+    match val {
+        0 => s.serialize_str(""),
+        1 => s.serialize_str("yes"),
+        _ => Err(serde::ser::Error::custom(format!("{val} is not a boolean"))),
+    }
+}
@@ -0,0 +1,390 @@
+//! Importing and exporting translations, and checking them against the original language sheets.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::lang::{Language, Languages};
+use crate::po::{PoEntry, PoFile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IssueKind {
+    /// The key exists in the original sheet but not in the translation, the original value is kept.
+    Missing,
+    /// The key or sheet doesn't exist in the original language.
+    Extra,
+    /// The `{0}`-style placeholders differ from the original value.
+    PlaceholderMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub sheet: String,
+    pub key: String,
+    pub detail: Option<String>,
+}
+
+/// Reads the `<lang>/<sheet>.json` layout written by `dump_language_keys`.
+pub fn read_dir(dir: &Path) -> Result<Languages> {
+    let mut languages = Languages::new();
+    for lang_dir in sorted_entries(dir)? {
+        if !lang_dir.is_dir() {
+            continue;
+        }
+        let lang = file_stem(&lang_dir)?;
+        let language: &mut Language = languages.entry(lang).or_default();
+        for sheet in sorted_entries(&lang_dir)? {
+            if sheet.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let data = std::fs::read_to_string(&sheet)?;
+            let keys = serde_json::from_str(&data)
+                .with_context(|| format!("failed to parse {}", sheet.display()))?;
+            language.insert(file_stem(&sheet)?, keys);
+        }
+    }
+    Ok(languages)
+}
+
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    lang: String,
+    sheet: String,
+    key: String,
+    value: String,
+}
+
+/// Reads a CSV file with the columns `lang,sheet,key,value`.
+pub fn read_csv(path: &Path) -> Result<Languages> {
+    let mut languages = Languages::new();
+    for row in csv::Reader::from_path(path)?.deserialize() {
+        let row: CsvRow = row?;
+        languages
+            .entry(row.lang)
+            .or_default()
+            .entry(row.sheet)
+            .or_default()
+            .insert(row.key, row.value);
+    }
+    Ok(languages)
+}
+
+/// Reads a `.po` file with `msgctxt "<sheet>/<key>"`. Untranslated entries are skipped.
+pub fn read_po(text: &str, lang: Option<&str>) -> Result<Languages> {
+    let po = PoFile::parse(text)?;
+    let Some(lang) = lang.or(po.language.as_deref()) else {
+        bail!("po file has no Language header, specify the language explicitly");
+    };
+
+    let mut language = Language::new();
+    for entry in po.entries {
+        if entry.str.is_empty() {
+            continue;
+        }
+        let context = entry.context.unwrap_or_default();
+        let Some((sheet, key)) = context.split_once('/') else {
+            bail!("msgctxt '{context}' is not of the form <sheet>/<key>");
+        };
+        language
+            .entry(sheet.to_owned())
+            .or_default()
+            .insert(key.to_owned(), entry.str);
+    }
+
+    Ok(Languages::from([(lang.to_owned(), language)]))
+}
+
+/// Reads a directory in the `dump_language_keys` layout, a `.csv` or a `.po` file.
+pub fn read(path: &Path, lang: Option<&str>) -> Result<Languages> {
+    if path.is_dir() {
+        return read_dir(path);
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => read_csv(path),
+        Some("po") => read_po(&std::fs::read_to_string(path)?, lang),
+        _ => bail!("unsupported translation format: {}", path.display()),
+    }
+}
+
+/// Exports `target` as a `.po` file with `msgid`s from `source`, e.g. English.
+///
+/// Keys with an empty source value are skipped, since an empty `msgid` is reserved for the header.
+pub fn to_po(target_lang: &str, source: &Language, target: &Language) -> PoFile {
+    let entries = source
+        .iter()
+        .flat_map(|(sheet, keys)| {
+            keys.iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(move |(key, value)| PoEntry {
+                    context: Some(format!("{sheet}/{key}")),
+                    id: value.clone(),
+                    str: target
+                        .get(sheet)
+                        .and_then(|keys| keys.get(key))
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+        })
+        .collect();
+
+    PoFile {
+        language: Some(target_lang.to_owned()),
+        entries,
+    }
+}
+
+/// `{0}`, `{name}` etc. occurring in a value, in order.
+pub fn placeholders(value: &str) -> Vec<&str> {
+    let mut placeholders = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start..start + len + 1];
+        if !placeholder[1..].contains(['{', ' ']) {
+            placeholders.push(placeholder);
+        }
+        rest = &rest[start + 1..];
+    }
+    placeholders
+}
+
+/// Markup tags like `<br>` or `<hpage>` occurring in a value, in order.
+pub fn markup_tags(value: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start..start + len + 1];
+        if !tag[1..].contains(['<', ' ']) && tag.len() > 2 {
+            tags.push(tag);
+        }
+        rest = &rest[start + 1..];
+    }
+    tags
+}
+
+fn sorted_placeholders(value: &str) -> Vec<&str> {
+    let mut placeholders = placeholders(value);
+    placeholders.sort_unstable();
+    placeholders
+}
+
+fn sorted_markup_tags(value: &str) -> Vec<&str> {
+    let mut tags = markup_tags(value);
+    tags.sort_unstable();
+    tags
+}
+
+#[derive(Debug, Default, Serialize)]
+#[allow(non_snake_case)]
+pub struct SheetCoverage {
+    pub total: usize,
+    pub translated: usize,
+    /// Keys of the reference language not present in this language.
+    pub missing: Vec<String>,
+    pub empty: Vec<String>,
+    /// Values identical to the reference language.
+    pub untranslated: Vec<String>,
+    pub placeholderMismatches: Vec<String>,
+    pub markupMismatches: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct LanguageCoverage {
+    /// Percentage of translated keys.
+    pub coverage: f32,
+    pub total: usize,
+    pub translated: usize,
+    pub sheets: IndexMap<String, SheetCoverage>,
+}
+
+/// Compares `language` to the `reference` language, usually English.
+///
+/// A key counts as translated if it is non-empty and differs from the reference, or if the reference is empty too.
+pub fn coverage(reference: &Language, language: &Language) -> LanguageCoverage {
+    let mut sheets = IndexMap::new();
+    for (sheet, reference_keys) in reference {
+        let keys = language.get(sheet);
+        let mut coverage = SheetCoverage::default();
+        for (key, reference_value) in reference_keys {
+            coverage.total += 1;
+            let Some(value) = keys.and_then(|keys| keys.get(key)) else {
+                coverage.missing.push(key.clone());
+                continue;
+            };
+            if reference_value.is_empty() {
+                coverage.translated += 1;
+                continue;
+            }
+
+            if value.is_empty() {
+                coverage.empty.push(key.clone());
+            } else if value == reference_value {
+                coverage.untranslated.push(key.clone());
+            } else {
+                coverage.translated += 1;
+            }
+            if sorted_placeholders(reference_value) != sorted_placeholders(value) {
+                coverage.placeholderMismatches.push(key.clone());
+            }
+            if sorted_markup_tags(reference_value) != sorted_markup_tags(value) {
+                coverage.markupMismatches.push(key.clone());
+            }
+        }
+        sheets.insert(sheet.clone(), coverage);
+    }
+
+    let total = sheets.values().map(|sheet| sheet.total).sum();
+    let translated = sheets.values().map(|sheet| sheet.translated).sum();
+    LanguageCoverage {
+        coverage: match total {
+            0 => 100.0,
+            total => translated as f32 / total as f32 * 100.0,
+        },
+        total,
+        translated,
+        sheets,
+    }
+}
+
+/// Summary table of all languages, followed by a table of sheets per language.
+pub fn coverage_markdown(report: &IndexMap<String, LanguageCoverage>) -> String {
+    fn count(
+        sheets: &IndexMap<String, SheetCoverage>,
+        f: impl Fn(&SheetCoverage) -> usize,
+    ) -> usize {
+        sheets.values().map(f).sum()
+    }
+
+    let header = "| Missing | Empty | Untranslated | Placeholder mismatches | Markup mismatches | Coverage |";
+    let mut md = String::from("# Translation coverage\n\n");
+    writeln!(md, "| Language | Keys | Translated {header}").unwrap();
+    md.push_str("|---|--:|--:|--:|--:|--:|--:|--:|--:|\n");
+    for (lang, coverage) in report {
+        let sheets = &coverage.sheets;
+        writeln!(
+            md,
+            "| {lang} | {} | {} | {} | {} | {} | {} | {} | {:.1}% |",
+            coverage.total,
+            coverage.translated,
+            count(sheets, |s| s.missing.len()),
+            count(sheets, |s| s.empty.len()),
+            count(sheets, |s| s.untranslated.len()),
+            count(sheets, |s| s.placeholderMismatches.len()),
+            count(sheets, |s| s.markupMismatches.len()),
+            coverage.coverage,
+        )
+        .unwrap();
+    }
+
+    for (lang, coverage) in report {
+        writeln!(md, "\n## {lang}\n").unwrap();
+        writeln!(md, "| Sheet | Keys | Translated {header}").unwrap();
+        md.push_str("|---|--:|--:|--:|--:|--:|--:|--:|--:|\n");
+        for (sheet, s) in &coverage.sheets {
+            let percentage = match s.total {
+                0 => 100.0,
+                total => s.translated as f32 / total as f32 * 100.0,
+            };
+            writeln!(
+                md,
+                "| {sheet} | {} | {} | {} | {} | {} | {} | {} | {percentage:.1}% |",
+                s.total,
+                s.translated,
+                s.missing.len(),
+                s.empty.len(),
+                s.untranslated.len(),
+                s.placeholderMismatches.len(),
+                s.markupMismatches.len(),
+            )
+            .unwrap();
+        }
+    }
+    md
+}
+
+pub fn validate(original: &Language, translation: &Language) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let issue = |kind, sheet: &str, key: &str, detail| Issue {
+        kind,
+        sheet: sheet.to_owned(),
+        key: key.to_owned(),
+        detail,
+    };
+
+    for (sheet, keys) in translation {
+        let Some(original_keys) = original.get(sheet) else {
+            issues.push(issue(IssueKind::Extra, sheet, "", None));
+            continue;
+        };
+        for (key, value) in keys {
+            let Some(original_value) = original_keys.get(key) else {
+                issues.push(issue(IssueKind::Extra, sheet, key, None));
+                continue;
+            };
+            let expected = sorted_placeholders(original_value);
+            let actual = sorted_placeholders(value);
+            if expected != actual {
+                let detail = format!("expected {expected:?}, got {actual:?}");
+                issues.push(issue(
+                    IssueKind::PlaceholderMismatch,
+                    sheet,
+                    key,
+                    Some(detail),
+                ));
+            }
+        }
+    }
+    for (sheet, keys) in original {
+        let translated = translation.get(sheet);
+        for key in keys.keys() {
+            if translated.is_none_or(|translated| !translated.contains_key(key)) {
+                issues.push(issue(IssueKind::Missing, sheet, key, None));
+            }
+        }
+    }
+
+    issues
+}
+
+/// The original language, with every known key replaced by its translation. Extra keys are dropped.
+pub fn patch(original: &Language, translation: &Language) -> Language {
+    original
+        .iter()
+        .map(|(sheet, keys)| {
+            let translated = translation.get(sheet);
+            let keys: IndexMap<String, String> = keys
+                .iter()
+                .map(|(key, value)| {
+                    let value = translated.and_then(|t| t.get(key)).unwrap_or(value);
+                    (key.clone(), value.clone())
+                })
+                .collect();
+            (sheet.clone(), keys)
+        })
+        .collect()
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let mut entries = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn file_stem(path: &Path) -> Result<String> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(ToOwned::to_owned)
+        .with_context(|| format!("invalid file name: {}", path.display()))
+}
@@ -0,0 +1,166 @@
+//! Helpers for objects read through their type tree as [`Value`], without a generated struct.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use rabex_env::handle::SerializedFileHandle;
+use rabex_env::rabex::objects::PPtr;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::lang::Language;
+
+/// Visits every value nested in `value` depth first, together with its field path like `targets[0].Counter`.
+///
+/// The children of a value are skipped if `f` returns `false`.
+pub fn visit<'a>(value: &'a Value, f: &mut impl FnMut(&str, &'a Value) -> bool) {
+    visit_inner(value, &mut String::new(), f);
+}
+
+fn visit_inner<'a>(
+    value: &'a Value,
+    path: &mut String,
+    f: &mut impl FnMut(&str, &'a Value) -> bool,
+) {
+    if !f(path, value) {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                visit_inner(value, path, f);
+                path.truncate(len);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                let len = path.len();
+                write!(path, "[{i}]").unwrap();
+                visit_inner(value, path, f);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Like [`visit`], but allows replacing values. The children of the new value are visited unless `f` returns `false`.
+pub fn visit_mut(value: &mut Value, f: &mut impl FnMut(&mut Value) -> bool) {
+    if !f(value) {
+        return;
+    }
+    match value {
+        Value::Object(map) => map.values_mut().for_each(|value| visit_mut(value, f)),
+        Value::Array(values) => values.iter_mut().for_each(|value| visit_mut(value, f)),
+        _ => {}
+    }
+}
+
+/// The value as a `PPtr`, if it is one.
+pub fn as_pptr(value: &Value) -> Option<PPtr> {
+    let map = value.as_object()?;
+    if map.len() != 2 || !map.contains_key("m_FileID") || !map.contains_key("m_PathID") {
+        return None;
+    }
+    PPtr::deserialize(value).ok()
+}
+
+/// Sheet and key of the value, if it is a `LocalisedString`.
+pub fn as_localised(value: &Value) -> Option<(&str, &str)> {
+    match (value.get("Sheet")?, value.get("Key")?) {
+        (Value::String(sheet), Value::String(key)) => Some((sheet, key)),
+        _ => None,
+    }
+}
+
+/// Every non-null `PPtr` in `value` with its field path, except the object's own `m_GameObject` and `m_Script`.
+pub fn object_pptrs(value: &Value) -> Vec<(String, PPtr)> {
+    let mut pptrs = Vec::new();
+    visit(value, &mut |path, value| {
+        if matches!(path, "m_GameObject" | "m_Script") {
+            return false;
+        }
+        let Some(pptr) = as_pptr(value) else {
+            return true;
+        };
+        if pptr.m_PathID != 0 {
+            pptrs.push((path.to_owned(), pptr));
+        }
+        false
+    });
+    pptrs
+}
+
+/// Replaces every `PPtr` in `value` with the result of `f`, skipping null pointers.
+pub fn map_pptrs(value: &mut Value, f: &mut impl FnMut(PPtr) -> Value) {
+    visit_mut(value, &mut |value| {
+        let Some(pptr) = as_pptr(value) else {
+            return true;
+        };
+        *value = match pptr.m_PathID {
+            0 => Value::Null,
+            _ => f(pptr),
+        };
+        false
+    });
+}
+
+/// Replaces every `LocalisedString` (`{"Sheet": .., "Key": ..}`) with its text in `lang`.
+///
+/// Empty strings become `null`, unknown keys are left as they are.
+pub fn resolve_localised(value: &mut Value, lang: &Language) {
+    visit_mut(value, &mut |value| {
+        let Some((sheet, key)) = as_localised(value) else {
+            return true;
+        };
+        if sheet.is_empty() && key.is_empty() {
+            *value = Value::Null;
+        } else if let Some(text) = lang
+            .get(&sheet.to_ascii_lowercase())
+            .and_then(|keys| keys.get(key))
+        {
+            *value = Value::String(text.clone());
+        }
+        false
+    });
+}
+
+#[derive(Deserialize)]
+#[allow(non_snake_case)]
+struct Named {
+    #[serde(default)]
+    m_Name: String,
+}
+
+/// Replaces every `PPtr` in `value` with the name of the object it points to.
+///
+/// Unnamed objects are rendered as `<ClassId pathId>`, pointers into other files as `<external fileId:pathId>`.
+pub fn resolve_pptr_names(
+    file: SerializedFileHandle,
+    value: &mut Value,
+    cache: &mut HashMap<(i32, i64), Value>,
+) {
+    map_pptrs(value, &mut |pptr| {
+        cache
+            .entry((pptr.m_FileID, pptr.m_PathID))
+            .or_insert_with(|| pptr_name(file.reborrow(), pptr))
+            .clone()
+    });
+}
+
+fn pptr_name(file: SerializedFileHandle, pptr: PPtr) -> Value {
+    let external = || format!("<external {}:{}>", pptr.m_FileID, pptr.m_PathID);
+    let Ok(object) = file.deref(pptr.typed::<Named>()) else {
+        return external().into();
+    };
+    match object.read() {
+        Ok(named) if !named.m_Name.is_empty() => named.m_Name.into(),
+        Ok(_) => format!("<{:?} {}>", object.class_id(), pptr.m_PathID).into(),
+        Err(_) => external().into(),
+    }
+}
@@ -0,0 +1,29 @@
+//! Problems with single objects which don't prevent dumping the rest.
+//!
+//! The library collects these instead of printing them, so binaries decide how to report them.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    /// Name of the object the warning is about, e.g. the `m_Name` of a tool.
+    pub object: String,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(object: impl Into<String>, message: impl Into<String>) -> Warning {
+        Warning {
+            object: object.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.object, self.message)
+    }
+}
@@ -0,0 +1,82 @@
+//! Export of language pairs as XLIFF, with one `<file>` per sheet.
+
+use std::fmt::Write;
+
+use quick_xml::escape::escape;
+
+use crate::lang::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XliffVersion {
+    V1_2,
+    V2_0,
+}
+
+/// Writes every key of `source` as a translation unit, with the value from `target` if present.
+pub fn write(
+    version: XliffVersion,
+    (source_lang, source): (&str, &Language),
+    (target_lang, target): (&str, &Language),
+) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let (source_lang, target_lang) = (escape(source_lang), escape(target_lang));
+    match version {
+        XliffVersion::V1_2 => out.push_str(
+            "<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n",
+        ),
+        XliffVersion::V2_0 => writeln!(
+            out,
+            "<xliff version=\"2.0\" xmlns=\"urn:oasis:names:tc:xliff:document:2.0\" srcLang=\"{source_lang}\" trgLang=\"{target_lang}\">"
+        )
+        .unwrap(),
+    }
+
+    for (sheet, keys) in source {
+        let target_keys = target.get(sheet);
+        let sheet = escape(sheet);
+        match version {
+            XliffVersion::V1_2 => {
+                writeln!(
+                    out,
+                    "  <file original=\"{sheet}\" source-language=\"{source_lang}\" target-language=\"{target_lang}\" datatype=\"plaintext\">"
+                )
+                .unwrap();
+                out.push_str("    <body>\n");
+            }
+            XliffVersion::V2_0 => writeln!(out, "  <file id=\"{sheet}\">").unwrap(),
+        }
+
+        for (key, value) in keys {
+            let translation = target_keys.and_then(|keys| keys.get(key));
+            let (key, value) = (escape(key), escape(value));
+            match version {
+                XliffVersion::V1_2 => {
+                    writeln!(out, "      <trans-unit id=\"{key}\">").unwrap();
+                    writeln!(out, "        <source>{value}</source>").unwrap();
+                    if let Some(translation) = translation {
+                        writeln!(out, "        <target>{}</target>", escape(translation)).unwrap();
+                    }
+                    out.push_str("      </trans-unit>\n");
+                }
+                XliffVersion::V2_0 => {
+                    writeln!(out, "    <unit id=\"{key}\">").unwrap();
+                    out.push_str("      <segment>\n");
+                    writeln!(out, "        <source>{value}</source>").unwrap();
+                    if let Some(translation) = translation {
+                        writeln!(out, "        <target>{}</target>", escape(translation)).unwrap();
+                    }
+                    out.push_str("      </segment>\n");
+                    out.push_str("    </unit>\n");
+                }
+            }
+        }
+
+        if version == XliffVersion::V1_2 {
+            out.push_str("    </body>\n");
+        }
+        out.push_str("  </file>\n");
+    }
+
+    out.push_str("</xliff>\n");
+    out
+}
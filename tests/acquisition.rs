@@ -0,0 +1,135 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_json};
+use serde_json::{Value, json};
+use silksong_data_dump::acquisition::AcquisitionIndex;
+use silksong_data_dump::generated::{CollectableItemRelicType, Quest, ShopItem};
+use silksong_data_dump::scenes::{ObjectKind, Scene, SceneObject};
+
+fn object(kind: ObjectKind, path: &str, data: Value) -> SceneObject {
+    SceneObject {
+        path: path.to_owned(),
+        kind,
+        script: format!("{kind:?}"),
+        persistentId: None,
+        item: None,
+        stock: Vec::new(),
+        data,
+    }
+}
+
+fn scene(name: &str, objects: Vec<SceneObject>) -> Scene {
+    Scene {
+        name: name.to_owned(),
+        rootObjects: Vec::new(),
+        objects,
+    }
+}
+
+#[test]
+fn acquisition() {
+    let shop = |stock: &[&str]| SceneObject {
+        stock: stock.iter().map(|name| (*name).to_owned()).collect(),
+        ..object(ObjectKind::Shop, "Shop Owner", json!({}))
+    };
+    let pickup = SceneObject {
+        item: Some("Straight Pin".to_owned()),
+        ..object(ObjectKind::Pickup, "Collectable Item Pickup", json!({}))
+    };
+
+    let mut index = AcquisitionIndex::default();
+    let mut warnings = Vec::new();
+    index.add_scene(
+        &scene("Bone_01", vec![pickup, shop(&["Straight Pin Shop"])]),
+        &mut warnings,
+    );
+    // sold directly and from a list
+    index.add_scene(
+        &scene(
+            "Bellhart_01",
+            vec![shop(&["Straight Pin Shop", "Bellhart Stock"])],
+        ),
+        &mut warnings,
+    );
+    index.add_scene(
+        &scene(
+            "Bellhart_02",
+            vec![object(
+                ObjectKind::RelicBoard,
+                "Relic Board",
+                json!({ "currencyType": 0 }),
+            )],
+        ),
+        &mut warnings,
+    );
+    index.add_shop_list("Bellhart Stock", &["Poison Pouch Shop".to_owned()]);
+    // lists no shop sells from are ignored
+    index.add_shop_list("Unused Stock", &["Flea Brew Shop".to_owned()]);
+
+    let shop_items: Vec<ShopItem> = fixture("shop_items.json");
+    let mut shop_items = shop_items.into_iter();
+    let straight_pin = shop_items.next().unwrap();
+    index.add_shop_item_source(&straight_pin, "Straight Pin".to_owned(), 30);
+    let poison_pouch = shop_items.next().unwrap();
+    index.add_shop_item_source(&poison_pouch, "Poison Pouch".to_owned(), 120);
+    let flea_brew = shop_items.next().unwrap();
+    index.add_shop_item_source(&flea_brew, "Flea Brew".to_owned(), 80);
+
+    let quests: Vec<Quest> = fixture("quests.json");
+    index.add_quest_reward(&quests[0], "Flea Brew".to_owned(), &[]);
+    index.add_quest_reward(
+        &quests[1],
+        "Money".to_owned(),
+        &["3 Mossgrub".to_owned(), "1 Moss Mother".to_owned()],
+    );
+
+    let relic_types: Vec<CollectableItemRelicType> = fixture("collectables.json");
+    for relic_type in &relic_types {
+        index.add_relic_type(relic_type, &mut warnings);
+    }
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_snapshot("acquisition.json", &to_json(&index));
+}
+
+#[test]
+fn relic_boards() {
+    let relic_types: Vec<CollectableItemRelicType> = fixture("collectables.json");
+
+    // without a board the currency of the reward is unknown
+    let mut index = AcquisitionIndex::default();
+    let mut warnings = Vec::new();
+    index.add_relic_type(&relic_types[0], &mut warnings);
+    assert!(index.items.is_empty());
+    let messages: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        ["Relic Skull: no relic board found, so the currency of the reward is unknown"]
+    );
+
+    let mut warnings = Vec::new();
+    index.add_scene(
+        &scene(
+            "Bellhart_02",
+            vec![
+                object(ObjectKind::RelicBoard, "Relic Board", json!({})),
+                object(
+                    ObjectKind::RelicBoard,
+                    "Shard Board",
+                    json!({ "currencyType": 1 }),
+                ),
+            ],
+        ),
+        &mut warnings,
+    );
+    index.add_relic_type(&relic_types[0], &mut warnings);
+    let messages: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        ["Bellhart_02/Relic Board: relic board RelicBoard has no known currencyType: null"]
+    );
+    let sources = &index.items["Shard"];
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0].count, 250);
+    assert_eq!(sources[0].scene.as_deref(), Some("Bellhart_02"));
+}
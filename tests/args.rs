@@ -0,0 +1,36 @@
+use silksong_data_dump::args::Args;
+
+fn parse(args: &[&str]) -> anyhow::Result<Args> {
+    Args::parse(
+        args.iter().map(|&arg| arg.to_owned()),
+        &["--force", "--fuzzy"],
+        &["--lang", "--key"],
+    )
+}
+
+#[test]
+fn flags_options_and_positional() {
+    let args = parse(&["out/languages", "--lang", "de", "--force", "extra"]).unwrap();
+    assert!(args.flag("--force"));
+    assert!(!args.flag("--fuzzy"));
+    assert_eq!(args.option("--lang"), Some("de"));
+    assert_eq!(args.option("--key"), None);
+    // the value of an option isn't positional, even if it looks like a path
+    assert_eq!(args.positional(), ["out/languages", "extra"]);
+}
+
+#[test]
+fn repeated_options() {
+    let args = parse(&["--key", "a", "--lang", "en", "--key", "b", "--lang", "de"]).unwrap();
+    assert_eq!(args.options_of("--key").collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(args.option("--lang"), Some("de"));
+}
+
+#[test]
+fn invalid_arguments() {
+    let error = parse(&["query", "--lang"]).unwrap_err();
+    assert_eq!(error.to_string(), "--lang requires a value");
+
+    let error = parse(&["--frce"]).unwrap_err();
+    assert_eq!(error.to_string(), "unknown argument '--frce'");
+}
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use silksong_data_dump::cache::{Cache, fingerprint_files};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("silksong-cache-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn cached_entries() {
+    let root = temp_dir("entries");
+    let cache = Cache::open_at(&root, "build1").unwrap();
+
+    let value: Vec<String> = cache
+        .get_or_insert_with("strings", || Ok(vec!["a".to_owned()]))
+        .unwrap();
+    assert_eq!(value, ["a"]);
+    let value: Vec<String> = cache
+        .get_or_insert_with("strings", || panic!("should be cached"))
+        .unwrap();
+    assert_eq!(value, ["a"]);
+
+    // entries of a different type are recomputed
+    let value: u32 = cache.get_or_insert_with("strings", || Ok(1)).unwrap();
+    assert_eq!(value, 1);
+
+    // entries can be grouped in directories
+    let value: u32 = cache
+        .get_or_insert_with("objects/tools/toolitems", || Ok(2))
+        .unwrap();
+    assert_eq!(value, 2);
+    assert!(root.join("build1/objects/tools/toolitems.json").exists());
+
+    // other fingerprints are removed, but nothing else in the directory, even if named like a fingerprint
+    std::fs::create_dir_all(root.join("0123456789abcdef")).unwrap();
+    std::fs::create_dir_all(root.join("unrelated")).unwrap();
+    Cache::open_at(&root, "build2").unwrap();
+    assert!(!root.join("build1").exists());
+    assert!(root.join("0123456789abcdef").exists());
+    assert!(root.join("unrelated").exists());
+    assert!(root.join("build2").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn fingerprint_changes_with_files() {
+    let dir = temp_dir("fingerprint");
+    let files = [PathBuf::from("globalgamemanagers")];
+    std::fs::write(dir.join("globalgamemanagers"), "a").unwrap();
+    let before = fingerprint_files(&dir, &files).unwrap();
+    assert_eq!(before, fingerprint_files(&dir, &files).unwrap());
+
+    std::fs::write(dir.join("globalgamemanagers"), "ab").unwrap();
+    assert_ne!(before, fingerprint_files(&dir, &files).unwrap());
+
+    assert!(fingerprint_files(Path::new("/nonexistent"), &files).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use rabex_env::rabex::objects::PPtr;
+use serde_json::json;
+use silksong_data_dump::catalog::{bundles_from_catalog, count_scripts, read_catalog_bundles};
+
+#[test]
+fn catalog_bundles() {
+    let catalog = json!({
+        "m_InternalIds": [
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}\\StandaloneWindows64\\dataassets_assets_assets/dataassets/costs.bundle",
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/StandaloneWindows64/scenes_scenes_scenes/tut_01.bundle",
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/StandaloneWindows64/scenes_scenes_scenes/tut_01.bundle",
+            "Assets/Data Assets/Costs.asset",
+            "https://example.com/remote.bundle",
+        ]
+    });
+
+    assert_eq!(
+        bundles_from_catalog(&catalog),
+        [
+            PathBuf::from("dataassets_assets_assets/dataassets/costs.bundle"),
+            PathBuf::from("scenes_scenes_scenes/tut_01.bundle"),
+        ]
+    );
+    assert!(bundles_from_catalog(&json!({})).is_empty());
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("silksong-catalog-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn catalog_files() {
+    let aa = temp_dir("files");
+    assert_eq!(read_catalog_bundles(&aa).unwrap(), None);
+
+    // a binary catalog can't be read, so it is an error instead of silently listing the directory
+    std::fs::write(aa.join("catalog.bin"), [0u8; 4]).unwrap();
+    let error = read_catalog_bundles(&aa).unwrap_err();
+    assert!(error.to_string().contains("only has a binary catalog.bin"));
+
+    // the JSON catalog is preferred
+    let catalog = json!({
+        "m_InternalIds": [
+            "{UnityEngine.AddressableAssets.Addressables.RuntimePath}/StandaloneWindows64/data/tools.bundle",
+        ]
+    });
+    std::fs::write(aa.join("catalog.json"), catalog.to_string()).unwrap();
+    assert_eq!(
+        read_catalog_bundles(&aa).unwrap(),
+        Some(vec![PathBuf::from("data/tools.bundle")])
+    );
+
+    std::fs::write(aa.join("catalog.json"), "{").unwrap();
+    assert!(read_catalog_bundles(&aa).is_err());
+
+    std::fs::remove_dir_all(&aa).unwrap();
+}
+
+#[test]
+fn script_counts() {
+    let pptr = |path_id: i64| PPtr {
+        m_FileID: 0,
+        m_PathID: path_id,
+    };
+    let scripts = [pptr(1), pptr(2), pptr(1), pptr(3), pptr(1), pptr(3)];
+
+    let mut reads = 0;
+    let mut warnings = Vec::new();
+    let counts = count_scripts(
+        &scripts,
+        |script| {
+            reads += 1;
+            match script.m_PathID {
+                1 => Ok(Some("ToolItemBasic".to_owned())),
+                2 => Ok(Some("CostReference".to_owned())),
+                _ => Err(anyhow::anyhow!("unknown class")),
+            }
+        },
+        "tools.bundle",
+        &mut warnings,
+    );
+
+    // every script is only read once, the most common ones come first
+    assert_eq!(reads, 3);
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        [
+            ("ToolItemBasic".to_owned(), 3),
+            (String::new(), 2),
+            ("CostReference".to_owned(), 1),
+        ]
+    );
+    let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        ["tools.bundle: couldn't read script 0/3: unknown class"]
+    );
+}
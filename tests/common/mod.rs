@@ -0,0 +1,59 @@
+//! Helpers shared by the snapshot tests.
+//!
+//! Snapshots live in `tests/snapshots`. Run with `UPDATE_SNAPSHOTS=1` to rewrite them after an intended change.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+fn tests_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+pub fn fixture<T: DeserializeOwned>(name: &str) -> T {
+    let path = tests_dir().join("fixtures").join(name);
+    let data = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    serde_json::from_str(&data).unwrap_or_else(|e| panic!("invalid fixture {name}: {e}"))
+}
+
+pub fn fixture_string(name: &str) -> String {
+    let path = tests_dir().join("fixtures").join(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+pub fn to_csv<T: Serialize>(rows: impl IntoIterator<Item = T>) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap() + "\n"
+}
+
+#[track_caller]
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = tests_dir().join("snapshots").join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}, run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    if expected != actual {
+        panic!(
+            "snapshot {name} doesn't match, run with UPDATE_SNAPSHOTS=1 to update it\n\n--- expected\n{expected}\n--- actual\n{actual}"
+        );
+    }
+}
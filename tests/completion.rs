@@ -0,0 +1,59 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_json};
+use serde_json::Value;
+use silksong_data_dump::completion::CompletionModel;
+use silksong_data_dump::generated::{EnemyJournalRecord, ToolItemBasic};
+use silksong_data_dump::warning::Warning;
+
+fn model(steel_soul: bool) -> CompletionModel {
+    let records: Vec<EnemyJournalRecord> = fixture("journal.json");
+    let mut tools: Vec<ToolItemBasic> = fixture("tools.json");
+    // not counted for completion
+    tools[3].isCounted = 0;
+
+    let mut model = CompletionModel::default();
+    let mut warnings = Vec::new();
+    for record in &records {
+        model.add_journal_record(record, steel_soul, &mut warnings);
+    }
+    assert_eq!(warnings, []);
+    for tool in &tools {
+        model.add_tool(tool);
+    }
+    model
+}
+
+#[test]
+fn progress() {
+    let save: Value = fixture("save.json");
+    let progress = model(false).progress(&save["playerData"]);
+
+    assert_snapshot("completion.json", &to_json(&progress));
+}
+
+#[test]
+fn progress_steel_soul() {
+    let save: Value = fixture("save.json");
+    let progress = model(true).progress(&save["playerData"]);
+
+    assert_snapshot("completion_steel_soul.json", &to_json(&progress));
+}
+
+#[test]
+fn unknown_required_type() {
+    let mut record = fixture::<Vec<EnemyJournalRecord>>("journal.json").remove(0);
+    record.isRequiredForCompletion = 1;
+    record.isAlwaysUnlocked = 0;
+    record.requiredType = 99;
+
+    let mut model = CompletionModel::default();
+    let mut warnings = Vec::new();
+    model.add_journal_record(&record, false, &mut warnings);
+
+    assert!(model.requirements.is_empty());
+    assert_eq!(
+        warnings,
+        [Warning::new(&record.m_Name, "unknown requiredType 99")]
+    );
+}
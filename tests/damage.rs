@@ -0,0 +1,74 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_snapshot, fixture, to_csv};
+use silksong_data_dump::damage::{self, DamageModel, ToolDamageTags};
+use silksong_data_dump::generated::{DamageTag, TimerGroup, ToolItemBasic};
+use silksong_data_dump::tables::DamageTagData;
+
+fn timer_for<'a>(
+    timers: &'a HashMap<String, TimerGroup>,
+    tag: &DamageTag,
+) -> Option<&'a TimerGroup> {
+    timers.get(&tag.damageCooldownTimer.m_PathID.to_string())
+}
+
+#[test]
+fn damage_tags() {
+    let tags: Vec<DamageTag> = fixture("damage_tags.json");
+    let mut timers: HashMap<String, TimerGroup> = fixture("timer_groups.json");
+
+    let rows = tags.into_iter().map(|tag| {
+        let timer = timers.remove(&tag.damageCooldownTimer.m_PathID.to_string());
+        DamageTagData::new(tag, timer).unwrap()
+    });
+    assert_snapshot("damagetags.csv", &to_csv(rows));
+}
+
+#[test]
+fn damage_comparison() {
+    let tags: Vec<DamageTag> = fixture("damage_tags.json");
+    let timers: HashMap<String, TimerGroup> = fixture("timer_groups.json");
+    let tools: Vec<ToolItemBasic> = fixture("tools.json");
+
+    let models: Vec<_> = tags
+        .iter()
+        .map(|tag| DamageModel::new(tag, timer_for(&timers, tag)).unwrap())
+        .collect();
+    let tools: Vec<_> = tools
+        .into_iter()
+        .map(|tool| (tool.m_Name.clone(), tool))
+        .collect();
+
+    let mut warnings = Vec::new();
+    let tool_tags = ToolDamageTags::find(&models, &mut warnings);
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_snapshot(
+        "damagecomparison.csv",
+        &to_csv(damage::compare(&models, tool_tags, &tools)),
+    );
+
+    // a second poison tag is ambiguous instead of adding a row per tag to every tool
+    let mut second = models[0].clone();
+    second.name = "Poison Tag 2".to_owned();
+    let mut models = models;
+    models.push(second);
+    let tool_tags = ToolDamageTags::find(&models, &mut warnings);
+    assert!(tool_tags.poison.is_none());
+    assert_eq!(warnings.len(), 1);
+    assert!(
+        damage::compare(&models, tool_tags, &tools)
+            .iter()
+            .all(|row| row.source.is_empty() || row.damageTag == "Lightning Tag")
+    );
+}
+
+#[test]
+fn tool_hit_limit() {
+    let tags: Vec<DamageTag> = fixture("damage_tags.json");
+    let lightning = DamageModel::new(&tags[1], None).unwrap();
+    assert_eq!(lightning.hit_limit, Some(10));
+    assert_eq!(lightning.with_hit_limit(4).hit_limit, Some(4));
+    assert_eq!(lightning.with_hit_limit(20).hit_limit, Some(10));
+}
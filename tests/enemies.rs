@@ -0,0 +1,33 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_json};
+use silksong_data_dump::enemies::EnemyIndex;
+use silksong_data_dump::generated::{EnemyJournalRecord, HealthManager};
+
+#[test]
+fn enemies() {
+    #[derive(serde::Deserialize)]
+    #[allow(non_snake_case)]
+    struct Instance {
+        gameObject: String,
+        healthManager: HealthManager,
+    }
+    let instances: Vec<Instance> = fixture("health_managers.json");
+    let records: Vec<EnemyJournalRecord> = fixture("journal.json");
+
+    let mut index = EnemyIndex::default();
+    for (i, instance) in instances.iter().enumerate() {
+        let scene = ["Bone_01", "Bone_05"][i % 2];
+        let contact_damage = (instance.healthManager.hp < 1000).then_some(1);
+        index.add_enemy(
+            scene,
+            &instance.gameObject,
+            &instance.healthManager,
+            contact_damage,
+        );
+    }
+    index.link_journal(&records);
+
+    let enemies: Vec<_> = index.enemies.values().collect();
+    assert_snapshot("enemies.json", &to_json(&enemies));
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::assert_snapshot;
+use indexmap::IndexMap;
+use serde_json::json;
+use silksong_data_dump::fsm::{self, Action, Fsm, State};
+
+#[test]
+fn decode_action_data() {
+    let mut byte_data = Vec::new();
+    byte_data.extend(42i32.to_le_bytes());
+    byte_data.extend(1.5f32.to_le_bytes());
+    byte_data.extend(b"FINISHED");
+    byte_data.extend([1, 0]);
+
+    let fsm = json!({
+        "name": "Control",
+        "startState": "Init",
+        "events": [{ "name": "FINISHED" }],
+        "globalTransitions": [],
+        "variables": {},
+        "states": [{
+            "name": "Init",
+            "transitions": [],
+            "actionData": {
+                "actionNames": ["HutongGames.PlayMaker.Actions.SetPlayerDataInt", "SendEvent"],
+                "customNames": ["", "Done"],
+                "actionEnabled": [1, 0],
+                "actionStartIndex": [0, 4],
+                "paramName": ["intName", "value", "delay", "sendEvent", "count", "custom"],
+                "paramDataType": [18, 0, 2, 23, 0, 99],
+                "paramDataPos": [0, 0, 4, 8, 16, 0],
+                "paramByteDataSize": [0, 4, 4, 8, 2, 0],
+                "byteData": byte_data,
+                "fsmStringParams": [{ "value": "silkMax" }],
+            },
+        }],
+    });
+
+    let mut warnings = Vec::new();
+    let fsm = Fsm::from_value("Bench".to_owned(), fsm, &mut warnings).unwrap();
+    let [set, send] = &fsm.states[0].actions[..] else {
+        panic!("expected two actions");
+    };
+
+    assert_eq!(set.r#type, "SetPlayerDataInt");
+    assert_eq!(set.name, None);
+    assert!(set.enabled);
+    assert_eq!(set.params["intName"], json!({ "value": "silkMax" }));
+    assert_eq!(set.params["value"], json!(42));
+    assert_eq!(set.params["delay"], json!(1.5));
+    assert_eq!(set.params["sendEvent"], json!({ "event": "FINISHED" }));
+
+    assert_eq!(send.name.as_deref(), Some("Done"));
+    assert!(!send.enabled);
+    assert_eq!(send.params["count"], json!(null));
+    assert_eq!(send.params["custom"], json!(null));
+    assert_eq!(fsm.sent_events().collect::<Vec<_>>(), ["FINISHED"]);
+
+    let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        [
+            "Bench - Control: state 'Init': action SendEvent: parameter 'count' has 2 bytes, expected 4",
+            "Bench - Control: state 'Init': action SendEvent: parameter 'custom' has unknown ParamDataType 99",
+        ]
+    );
+}
+
+#[test]
+fn mismatched_action_data() {
+    let fsm = json!({
+        "name": "Control",
+        "startState": "Init",
+        "events": [],
+        "globalTransitions": [],
+        "variables": {},
+        "states": [{
+            "name": "Init",
+            "transitions": [],
+            "actionData": {
+                "actionNames": ["Wait", "SendEvent", "Broken"],
+                "customNames": [],
+                "actionEnabled": [],
+                "actionStartIndex": [0, 1],
+                "paramName": ["time", "delay", "extra"],
+                "paramDataType": [2],
+                "paramDataPos": [0, 0],
+                "paramByteDataSize": [4, 4],
+                "byteData": 2.0f32.to_le_bytes(),
+            },
+        }],
+    });
+
+    let mut warnings = Vec::new();
+    let fsm = Fsm::from_value("Bench".to_owned(), fsm, &mut warnings).unwrap();
+    let [wait, send, broken] = &fsm.states[0].actions[..] else {
+        panic!("expected three actions");
+    };
+    assert_eq!(wait.params["time"], json!(2.0));
+    assert_eq!(send.params["delay"], json!(null));
+    assert_eq!(send.params["extra"], json!(null));
+    assert!(broken.params.is_empty());
+
+    let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        [
+            "Bench - Control: state 'Init': action SendEvent: parameter 'delay' is missing from paramDataType",
+            "Bench - Control: state 'Init': action SendEvent: parameter 'extra' is missing from paramDataPos",
+            "Bench - Control: state 'Init': action SendEvent: parameter 'extra' is missing from paramByteDataSize",
+            "Bench - Control: state 'Init': action SendEvent: parameter 'extra' is missing from paramDataType",
+            "Bench - Control: state 'Init': action Broken is missing from actionStartIndex",
+        ]
+    );
+}
+
+#[test]
+fn fsm_dot() {
+    let fsm = Fsm {
+        gameObject: "Bench".to_owned(),
+        name: "Bench Control".to_owned(),
+        startState: "Idle".to_owned(),
+        events: vec!["FINISHED".to_owned(), "SIT".to_owned()],
+        globalTransitions: vec![fsm::Transition {
+            event: "RESET".to_owned(),
+            toState: "Idle".to_owned(),
+        }],
+        states: vec![
+            State {
+                name: "Idle".to_owned(),
+                transitions: vec![fsm::Transition {
+                    event: "SIT".to_owned(),
+                    toState: "Rest".to_owned(),
+                }],
+                actions: Vec::new(),
+            },
+            State {
+                name: "Rest".to_owned(),
+                transitions: vec![fsm::Transition {
+                    event: "FINISHED".to_owned(),
+                    toState: "Idle".to_owned(),
+                }],
+                actions: vec![Action {
+                    r#type: "SendEvent".to_owned(),
+                    name: None,
+                    enabled: true,
+                    params: IndexMap::from([(
+                        "sendEvent".to_owned(),
+                        json!({ "event": "BENCHED" }),
+                    )]),
+                }],
+            },
+        ],
+        variables: json!({}),
+    };
+
+    assert_eq!(fsm.sent_events().collect::<Vec<_>>(), ["BENCHED"]);
+    assert_snapshot("fsm.dot", &fsm.to_dot());
+}
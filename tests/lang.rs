@@ -0,0 +1,219 @@
+mod common;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use common::{assert_snapshot, fixture, fixture_string, to_json};
+use indexmap::IndexMap;
+use silksong_data_dump::lang::{
+    AssetFormat, DEFAULT_KEY, GAME_MANAGERS, Language, LanguageConfig, LoadedLanguages,
+    NamingScheme, Precedence, decode_language_asset, read_language_asset, write_language_asset,
+    write_language_asset_with, write_language_assets,
+};
+
+#[test]
+fn decode_encrypted_asset() {
+    let script = fixture_string("EN_Tools.txt");
+    let keys = read_language_asset(script.trim()).unwrap();
+
+    assert_snapshot("EN_Tools.json", &to_json(&keys));
+}
+
+#[test]
+fn invalid_language_asset() {
+    assert!(read_language_asset("not base64!").is_err());
+    assert!(read_language_asset("AAAA").is_err());
+}
+
+#[test]
+fn language_asset_roundtrip() {
+    let language: Language = fixture("language.json");
+
+    let assets = write_language_assets("EN", &language).unwrap();
+    assert_eq!(
+        assets.keys().collect::<Vec<_>>(),
+        ["EN_tools", "EN_journal"]
+    );
+
+    for (sheet, keys) in &language {
+        let decoded = read_language_asset(&assets[&format!("EN_{sheet}")]).unwrap();
+        assert_eq!(&decoded, keys);
+    }
+}
+
+#[test]
+fn language_asset_escaping() {
+    let script = fixture_string("EN_Tools.txt");
+    let keys = read_language_asset(script.trim()).unwrap();
+
+    let reencoded = write_language_asset(&keys).unwrap();
+    assert_eq!(read_language_asset(&reencoded).unwrap(), keys);
+}
+
+#[test]
+fn detect_asset_format() {
+    let language: Language = fixture("language.json");
+    let keys = &language["tools"];
+    let other_key = b"0123456789abcdef0123456789abcdef".to_vec();
+
+    let plain = LanguageConfig {
+        keys: Vec::new(),
+        ..Default::default()
+    };
+    let xml = write_language_asset_with(keys, &plain).unwrap();
+    let (decoded, format) = decode_language_asset(&xml, &plain).unwrap();
+    assert_eq!((&decoded, format), (keys, AssetFormat::PlainXml));
+
+    let base64 = BASE64_STANDARD.encode(&xml);
+    let (decoded, format) = decode_language_asset(&base64, &plain).unwrap();
+    assert_eq!((&decoded, format), (keys, AssetFormat::Base64Xml));
+
+    let other = LanguageConfig {
+        keys: vec![other_key.clone()],
+        ..Default::default()
+    };
+    let encrypted = write_language_asset_with(keys, &other).unwrap();
+    let candidates = LanguageConfig {
+        keys: vec![DEFAULT_KEY.to_vec(), other_key],
+        ..Default::default()
+    };
+    let (decoded, format) = decode_language_asset(&encrypted, &candidates).unwrap();
+    assert_eq!((&decoded, format), (keys, AssetFormat::Encrypted(1)));
+
+    let error = decode_language_asset(&encrypted, &LanguageConfig::default()).unwrap_err();
+    assert!(format!("{error:#}").contains("couldn't be decrypted with any key"));
+
+    let short_key = LanguageConfig {
+        keys: vec![b"short".to_vec()],
+        ..Default::default()
+    };
+    assert!(write_language_asset_with(keys, &short_key).is_err());
+}
+
+#[test]
+fn naming_scheme() {
+    assert_eq!(
+        NamingScheme::LanguageFirst('_').split("en_Journal_Extra"),
+        Some(("en", "Journal_Extra"))
+    );
+    assert_eq!(
+        NamingScheme::SheetFirst('.').split("Journal.de"),
+        Some(("de", "Journal"))
+    );
+    assert_eq!(NamingScheme::LanguageFirst('_').split("README"), None);
+    assert_eq!(NamingScheme::LanguageFirst('_').split("en_"), None);
+    assert_eq!(
+        NamingScheme::SheetFirst('-').join("fr", "Tools"),
+        "Tools-fr"
+    );
+}
+
+#[test]
+fn language_precedence() {
+    let sheet = |value: &str| IndexMap::from([("KEY".to_owned(), value.to_owned())]);
+
+    for (precedence, expected) in [
+        (Precedence::Bundles, ("patch", "dlc.bundle")),
+        (Precedence::GameManagers, ("base", GAME_MANAGERS)),
+    ] {
+        let mut loaded = LoadedLanguages::default();
+        assert!(loaded.insert(GAME_MANAGERS, "en", "tools", sheet("base"), precedence));
+        loaded.insert("dlc.bundle", "en", "tools", sheet("patch"), precedence);
+        loaded.insert("dlc.bundle", "en", "journal", sheet("new"), precedence);
+
+        assert_eq!(loaded.languages["en"]["tools"]["KEY"], expected.0);
+        assert_eq!(loaded.origins["en"]["tools"], expected.1);
+        assert_eq!(loaded.origins["en"]["journal"], "dlc.bundle");
+        assert!(loaded.warnings.is_empty());
+    }
+
+    // between bundles the later one wins, but it's reported
+    let mut loaded = LoadedLanguages::default();
+    loaded.insert("a.bundle", "en", "tools", sheet("a"), Precedence::Bundles);
+    loaded.insert("b.bundle", "EN", "Tools", sheet("b"), Precedence::Bundles);
+    assert_eq!(loaded.languages["en"]["tools"]["KEY"], "b");
+    let warnings: Vec<String> = loaded.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings,
+        ["en/tools: language sheet exists in both a.bundle and b.bundle"]
+    );
+}
+
+#[test]
+fn resource_manager_languages() {
+    let script = fixture_string("EN_Tools.txt");
+    let config = LanguageConfig::default();
+
+    let mut loaded = LoadedLanguages::default();
+    loaded
+        .add_resource("EN_Tools", script.trim(), &config)
+        .unwrap();
+    assert_eq!(
+        loaded.languages["en"]["tools"],
+        read_language_asset(script.trim()).unwrap()
+    );
+    assert_eq!(loaded.origins["en"]["tools"], GAME_MANAGERS);
+
+    let error = loaded.add_resource("README", "", &config).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("doesn't match the naming scheme")
+    );
+    assert!(loaded.add_resource("EN_Broken", "AAAA", &config).is_err());
+    assert_eq!(loaded.languages["en"].len(), 1);
+}
+
+#[test]
+fn bundle_overrides_resource_manager() {
+    let script = fixture_string("EN_Tools.txt");
+    let keys = read_language_asset(script.trim()).unwrap();
+    let patched =
+        write_language_asset(&IndexMap::from([("KEY".to_owned(), "patched".to_owned())])).unwrap();
+
+    for (precedence, expected) in [
+        (Precedence::Bundles, "dlc.bundle"),
+        (Precedence::GameManagers, GAME_MANAGERS),
+    ] {
+        let config = LanguageConfig {
+            precedence,
+            ..Default::default()
+        };
+        let mut loaded = LoadedLanguages::default();
+        loaded
+            .add_resource("en_tools", script.trim(), &config)
+            .unwrap();
+        // named like the asset, not like the lowercased container path
+        loaded
+            .add_bundle_asset("dlc.bundle", "EN_Tools", &patched, &config)
+            .unwrap();
+        loaded
+            .add_bundle_asset("dlc.bundle", "README", "not a language", &config)
+            .unwrap();
+
+        assert_eq!(loaded.languages.keys().collect::<Vec<_>>(), ["en"]);
+        assert_eq!(loaded.languages["en"].keys().collect::<Vec<_>>(), ["tools"]);
+        assert_eq!(loaded.origins["en"]["tools"], expected);
+        let tools = &loaded.languages["en"]["tools"];
+        match precedence {
+            Precedence::Bundles => assert_eq!(tools["KEY"], "patched"),
+            Precedence::GameManagers => assert_eq!(tools, &keys),
+        }
+    }
+
+    let mut loaded = LoadedLanguages::default();
+    let config = LanguageConfig::default();
+    loaded
+        .add_resource("en_tools", script.trim(), &config)
+        .unwrap();
+    // broken assets are only reported for known languages
+    assert!(
+        loaded
+            .add_bundle_asset("dlc.bundle", "EN_Broken", "AAAA", &config)
+            .is_err()
+    );
+    assert!(
+        loaded
+            .add_bundle_asset("dlc.bundle", "XX_Broken", "AAAA", &config)
+            .is_ok()
+    );
+}
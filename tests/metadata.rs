@@ -0,0 +1,24 @@
+use silksong_data_dump::metadata::{GameMetadata, parse_app_info};
+
+#[test]
+fn app_info() {
+    assert_eq!(
+        parse_app_info("Team Cherry\r\nHollow Knight Silksong"),
+        (
+            "Team Cherry".to_owned(),
+            "Hollow Knight Silksong".to_owned()
+        )
+    );
+    assert_eq!(parse_app_info(""), (String::new(), String::new()));
+}
+
+#[test]
+fn version_string() {
+    let mut metadata = GameMetadata {
+        version: "1.0.28324".to_owned(),
+        ..Default::default()
+    };
+    assert_eq!(metadata.version_string(), "1.0.28324");
+    metadata.steamBuildId = Some(19911222);
+    assert_eq!(metadata.version_string(), "1.0.28324 (build 19911222)");
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_csv};
+use silksong_data_dump::fsm::Fsm;
+use silksong_data_dump::generated::ToolItemBasic;
+use silksong_data_dump::playerdata::PlayerDataIndex;
+
+#[test]
+fn player_data() {
+    let tools: Vec<ToolItemBasic> = fixture("tools.json");
+
+    let mut index = PlayerDataIndex::default();
+    for tool in &tools {
+        index.add_tool(tool);
+    }
+    index.write("hasPoisonPouch", "ShopItem Poison Pouch");
+    index.read("geo", "Quest Rosary Debt");
+
+    assert_snapshot("playerdata.csv", &to_csv(index.table()));
+}
+
+fn accesses(index: &PlayerDataIndex, field: &str) -> (Vec<String>, Vec<String>) {
+    let accesses = &index.fields[field];
+    (
+        accesses.read_by.iter().cloned().collect(),
+        accesses.written_by.iter().cloned().collect(),
+    )
+}
+
+#[test]
+fn fsm_reads_and_writes() {
+    let mut warnings = Vec::new();
+    let fsm = Fsm::from_value(
+        "Dash".to_owned(),
+        fixture("playerdata_fsm.json"),
+        &mut warnings,
+    )
+    .unwrap();
+    assert!(warnings.is_empty(), "{warnings:?}");
+
+    let mut index = PlayerDataIndex::default();
+    index.add_fsm(&fsm, "Tut_01");
+    let source = || vec!["FSM Tut_01/Dash-Dash Control".to_owned()];
+
+    // `PlayerDataBoolTest` only reads the field `SetPlayerDataBool` writes
+    assert_eq!(accesses(&index, "hasDash"), (source(), source()));
+    assert_eq!(accesses(&index, "geo"), (source(), vec![]));
+    assert_eq!(accesses(&index, "silkMax"), (vec![], source()));
+    // string parameters of other actions aren't field names, and empty strings are skipped
+    assert_eq!(
+        index.fields.keys().collect::<Vec<_>>(),
+        ["hasDash", "geo", "silkMax"]
+    );
+}
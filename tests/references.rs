@@ -0,0 +1,149 @@
+mod common;
+
+use common::{assert_snapshot, to_json};
+use rabex_env::rabex::objects::PPtr;
+use silksong_data_dump::cache::CachedBundle;
+use silksong_data_dump::generated::IntReference;
+use silksong_data_dump::references::{ReferenceIndex, ReferenceTarget};
+
+fn reference(script: i64, name: &str, value: i32) -> IntReference {
+    serde_json::from_value(serde_json::json!({
+        "m_GameObject": { "m_FileID": 0, "m_PathID": 0 },
+        "m_Enabled": 1,
+        "m_Script": { "m_FileID": 1, "m_PathID": script },
+        "m_Name": name,
+        "value": value,
+    }))
+    .unwrap()
+}
+
+fn pptr(file_id: i32, path_id: i64) -> serde_json::Value {
+    serde_json::json!({ "m_FileID": file_id, "m_PathID": path_id })
+}
+
+fn target(path_id: i64, script: i64, name: &str) -> Option<ReferenceTarget> {
+    Some(ReferenceTarget {
+        path_id,
+        script,
+        name: name.to_owned(),
+    })
+}
+
+#[test]
+fn references() {
+    let mut index = ReferenceIndex::default();
+    // same name, different scripts
+    index.add_reference("CostReference", 10, reference(1, "Pin", 30));
+    index.add_reference("DamageReference", 20, reference(2, "Pin", 5));
+    index.add_reference("DamageReference", 30, reference(2, "Unused", 1));
+
+    let shop_item = serde_json::json!({
+        "m_Name": "Pin Shop Item",
+        "costReference": pptr(1, 10),
+        "other": pptr(1, 99),
+    });
+    let tool = serde_json::json!({
+        "m_Name": "Straight Pin",
+        "damage": [pptr(1, 20)],
+        // the path ID of a reference, but in another file pointing to something else
+        "icon": pptr(1, 30),
+    });
+
+    let mut resolve = |pptr: PPtr| match pptr.m_PathID {
+        10 => target(10, 1, "Pin"),
+        20 => target(20, 2, "Pin"),
+        _ => None,
+    };
+    let mut partial = index.without_usages();
+    partial.add_value("shopitems", "ShopItem", &shop_item, &mut resolve);
+    index.merge(partial);
+    index.add_value("toolitems", "ToolItemBasic", &tool, &mut resolve);
+
+    assert_snapshot("references.json", &to_json(&index));
+}
+
+#[test]
+fn colliding_path_ids() {
+    let mut index = ReferenceIndex::default();
+    // two references from different bundles sharing a path ID
+    index.add_reference("CostReference", 10, reference(1, "Pin", 30));
+    index.add_reference("DamageReference", 10, reference(2, "Needle", 5));
+
+    let shop_item = serde_json::json!({
+        "m_Name": "Pin Shop Item",
+        "costReference": pptr(1, 10),
+    });
+    let tool = serde_json::json!({
+        "m_Name": "Needle",
+        "damage": pptr(2, 10),
+    });
+
+    // the file ID decides which of the two the pointer resolves to
+    let mut resolve = |pptr: PPtr| match pptr.m_FileID {
+        1 => target(pptr.m_PathID, 1, "Pin"),
+        2 => target(pptr.m_PathID, 2, "Needle"),
+        _ => None,
+    };
+    index.add_value("shopitems", "ShopItem", &shop_item, &mut resolve);
+    index.add_value("toolitems", "ToolItemBasic", &tool, &mut resolve);
+
+    let used_by = |key: &str| {
+        index.references[key]
+            .usedBy
+            .iter()
+            .map(|usage| usage.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(used_by("CostReference/Pin"), ["Pin Shop Item"]);
+    assert_eq!(used_by("DamageReference/Needle"), ["Needle"]);
+}
+
+#[test]
+fn cached_bundle_usages() {
+    let costs: CachedBundle = serde_json::from_value(serde_json::json!({
+        "objects": [
+            {
+                "path_id": 10,
+                "script": "CostReference",
+                "value": {
+                    "m_GameObject": { "m_FileID": 0, "m_PathID": 0 },
+                    "m_Enabled": 1,
+                    "m_Script": { "m_FileID": 1, "m_PathID": 1 },
+                    "m_Name": "Pin",
+                    "value": 30,
+                },
+            },
+            { "path_id": 11, "script": "Other", "value": { "m_Name": "Unrelated" } },
+        ],
+        "targets": [],
+    }))
+    .unwrap();
+    let shop: CachedBundle = serde_json::from_value(serde_json::json!({
+        "objects": [{
+            "path_id": 1,
+            "script": "ShopItem",
+            "value": {
+                "m_Name": "Pin Shop Item",
+                "costReference": pptr(2, 10),
+                // same path ID, but a different file
+                "icon": pptr(3, 10),
+            },
+        }],
+        "targets": [[2, { "path_id": 10, "script": 1, "name": "Pin" }]],
+    }))
+    .unwrap();
+
+    let mut index = ReferenceIndex::default();
+    index.add_references(&costs, "CostReference").unwrap();
+    index.add_usages("shopitems", &shop);
+
+    assert_eq!(index.references.len(), 1);
+    let usages = &index.references["CostReference/Pin"];
+    assert_eq!(usages.value, 30);
+    let fields: Vec<&str> = usages
+        .usedBy
+        .iter()
+        .map(|usage| usage.field.as_str())
+        .collect();
+    assert_eq!(fields, ["costReference"]);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::fixture;
+use silksong_data_dump::generated::ToolItemBasic;
+use silksong_data_dump::generated_impls::{FULL_REPLENISH_COST, ONE_FOR_ONE_REPLENISH_COST};
+use silksong_data_dump::warning::Warning;
+
+fn tool(name: &str) -> ToolItemBasic {
+    fixture::<Vec<ToolItemBasic>>("tools.json")
+        .into_iter()
+        .find(|tool| tool.m_Name == name)
+        .unwrap()
+}
+
+#[test]
+fn replenish_costs() {
+    let mut warnings = Vec::new();
+
+    // `Percentage` with a storage of 20
+    let pin = tool("Straight Pin");
+    assert_eq!(
+        pin.replenish_cost(&mut warnings).unwrap(),
+        Some(FULL_REPLENISH_COST / 20.0)
+    );
+    // `OneForOne` with a multiplier of 2
+    let brew = tool("Flea Brew");
+    assert_eq!(
+        brew.replenish_cost(&mut warnings).unwrap(),
+        Some(ONE_FOR_ONE_REPLENISH_COST * 2.0)
+    );
+    // not replenished
+    let pouch = tool("Poison Pouch");
+    assert_eq!(pouch.replenish_cost(&mut warnings).unwrap(), None);
+
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn custom_replenish_cost() {
+    let mut tool = tool("Straight Pin");
+    tool.replenishUsage = 2;
+
+    let mut warnings = Vec::new();
+    assert_eq!(tool.replenish_cost(&mut warnings).unwrap(), None);
+    assert_eq!(
+        warnings,
+        [Warning::new(
+            "Straight Pin",
+            "custom replenish usage, the cost isn't known"
+        )]
+    );
+}
+
+#[test]
+fn unknown_replenish_usage() {
+    let mut tool = tool("Straight Pin");
+    tool.replenishUsage = 7;
+    assert!(tool.replenish_cost(&mut Vec::new()).is_err());
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::assert_snapshot;
+use serde_json::json;
+use silksong_data_dump::dot;
+use silksong_data_dump::rooms::RoomGraph;
+use silksong_data_dump::scenes::{ObjectKind, Scene, SceneObject};
+
+fn scene(name: &str, transitions: &[(&str, &str, &str)]) -> Scene {
+    Scene {
+        name: name.to_owned(),
+        rootObjects: vec!["_Transitions".to_owned()],
+        objects: transitions
+            .iter()
+            .map(|(gate, target_scene, target_gate)| SceneObject {
+                path: format!("_Transitions/{gate}"),
+                kind: ObjectKind::Transition,
+                script: "TransitionPoint".to_owned(),
+                persistentId: None,
+                item: None,
+                stock: Vec::new(),
+                data: json!({ "targetScene": target_scene, "entryPoint": target_gate }),
+            })
+            .collect(),
+    }
+}
+
+fn room_graph() -> RoomGraph {
+    let mut graph = RoomGraph::default();
+    graph.add_scene(&scene("Tut_01", &[("right1", "Tut_02", "left1")]));
+    graph.add_scene(&scene(
+        "Tut_02",
+        &[
+            ("left1", "Tut_01", "right1"),
+            ("right1", "Bone_01", "left1"),
+            ("top1", "Tut_03", "bot1"),
+        ],
+    ));
+    graph.add_scene(&scene("Tut_03", &[("bot1", "Tut_02", "top1")]));
+    graph.add_scene(&scene(
+        "Bone_01",
+        &[("left1", "Tut_02", "right1"), ("door_dream", "", "")],
+    ));
+    graph
+}
+
+#[test]
+fn rooms_dot() {
+    assert_snapshot("rooms.dot", &room_graph().to_dot());
+}
+
+#[test]
+fn dot_quoting() {
+    assert_eq!(dot::quote("Tut_01"), "\"Tut_01\"");
+    assert_eq!(dot::quote("a \"b\"\\c\nd"), "\"a \\\"b\\\"\\\\c\\nd\"");
+
+    let mut graph = RoomGraph::default();
+    graph.add_scene(&scene("Quote\"s", &[("door \"1\"", "Tut_01", "left1")]));
+    assert!(
+        graph
+            .to_dot()
+            .contains(r#""Quote\"s" -> "Tut_01" [label="door \"1\" -> left1"];"#)
+    );
+}
+
+#[test]
+fn rooms_shortest_path() {
+    let graph = room_graph();
+
+    let path = graph.shortest_path("Tut_01", "Tut_03").unwrap();
+    let gates: Vec<_> = path
+        .iter()
+        .map(|t| format!("{}/{}", t.scene, t.gate))
+        .collect();
+    assert_eq!(gates, ["Tut_01/right1", "Tut_02/top1"]);
+
+    assert_eq!(graph.shortest_path("Tut_02", "Tut_02").unwrap().len(), 0);
+    assert!(graph.shortest_path("Tut_01", "Abyss_01").is_none());
+}
@@ -0,0 +1,99 @@
+use serde_json::json;
+use silksong_data_dump::scenes::{Hierarchy, HierarchyNode, ObjectKind, Scene, SceneObject};
+
+#[test]
+fn scene_hierarchy() {
+    let node = |transform: i64, game_object: i64, father: i64, name: &str| HierarchyNode {
+        transform,
+        game_object,
+        father,
+        name: name.to_owned(),
+    };
+    let hierarchy = Hierarchy::new(&[
+        node(10, 1, 0, "_Transitions"),
+        node(20, 2, 10, "right1"),
+        node(30, 3, 0, "_Scenery"),
+        node(40, 4, 30, "Bench"),
+        node(50, 5, 40, "Seat"),
+        // parent transform missing from the file
+        node(60, 6, 99, "Orphan"),
+    ]);
+
+    assert_eq!(hierarchy.roots(), ["_Transitions", "_Scenery", "Orphan"]);
+    let paths: Vec<_> = (1..=6).map(|go| hierarchy.path(go)).collect();
+    assert_eq!(
+        paths,
+        [
+            "_Transitions",
+            "_Transitions/right1",
+            "_Scenery",
+            "_Scenery/Bench",
+            "_Scenery/Bench/Seat",
+            "Orphan",
+        ]
+    );
+}
+
+#[test]
+fn scene_objects() {
+    let node = |transform: i64, game_object: i64, father: i64, name: &str| HierarchyNode {
+        transform,
+        game_object,
+        father,
+        name: name.to_owned(),
+    };
+    let hierarchy = Hierarchy::new(&[
+        node(10, 1, 0, "_Items"),
+        node(20, 2, 10, "Flea Jar"),
+        node(30, 3, 0, "_Transitions"),
+        node(40, 4, 30, "left1"),
+    ]);
+
+    let jar = SceneObject::new(
+        ObjectKind::PersistentBool,
+        "PersistentBoolItem".to_owned(),
+        json!({
+            "m_GameObject": { "m_FileID": 0, "m_PathID": 2 },
+            "itemData": { "ID": "Flea Jar", "SceneName": "Bone_01" },
+        }),
+        &hierarchy,
+    );
+    assert_eq!(jar.path, "_Items/Flea Jar");
+    assert_eq!(jar.persistentId.as_deref(), Some("Flea Jar"));
+
+    // only persistent bools have an ID, even if other components have `itemData`
+    let gate = SceneObject::new(
+        ObjectKind::Transition,
+        "TransitionPoint".to_owned(),
+        json!({
+            "m_GameObject": { "m_FileID": 0, "m_PathID": 4 },
+            "itemData": { "ID": "left1" },
+        }),
+        &hierarchy,
+    );
+    assert_eq!(gate.path, "_Transitions/left1");
+    assert_eq!(gate.persistentId, None);
+
+    let detached = SceneObject::new(
+        ObjectKind::PersistentBool,
+        "PersistentBoolItem".to_owned(),
+        json!({ "itemData": {} }),
+        &hierarchy,
+    );
+    assert_eq!(detached.path, "");
+    assert_eq!(detached.persistentId, None);
+
+    let scene = Scene {
+        name: "Bone_01".to_owned(),
+        rootObjects: hierarchy.roots(),
+        objects: vec![jar, gate, detached],
+    };
+    assert_eq!(scene.objects_of(ObjectKind::PersistentBool).count(), 2);
+    assert_eq!(
+        scene
+            .find_persistent("Flea Jar")
+            .map(|object| &*object.path),
+        Some("_Items/Flea Jar")
+    );
+    assert!(scene.find_persistent("left1").is_none());
+}
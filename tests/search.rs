@@ -0,0 +1,84 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_json};
+use serde_json::json;
+use silksong_data_dump::lang::{Language, Languages};
+use silksong_data_dump::localised::LocalisedIndex;
+use silksong_data_dump::search::{SearchOptions, search};
+
+fn languages() -> Languages {
+    let en: Language = fixture("language.json");
+    let mut fr = en.clone();
+    fr["tools"]["STRAIGHT_PIN_NAME"] = "Aiguille <b>droite</b>".to_owned();
+    Languages::from([("EN".to_owned(), en), ("FR".to_owned(), fr)])
+}
+
+fn keys(matches: &[silksong_data_dump::search::SearchMatch]) -> Vec<String> {
+    matches
+        .iter()
+        .map(|m| format!("{} {}/{}", m.lang, m.sheet, m.key))
+        .collect()
+}
+
+#[test]
+fn search_exact_and_fuzzy() {
+    let languages = languages();
+
+    let exact = search(&languages, "straight PIN", &SearchOptions::default());
+    assert_eq!(keys(&exact), ["EN tools/STRAIGHT_PIN_NAME"]);
+
+    let options = SearchOptions {
+        lang: Some("FR".to_owned()),
+        fuzzy: false,
+    };
+    assert!(search(&languages, "aiguille droite", &options).is_empty());
+
+    let fuzzy = SearchOptions {
+        fuzzy: true,
+        ..options
+    };
+    let matches = search(&languages, "DROITE aiguille", &fuzzy);
+    assert_eq!(keys(&matches), ["FR tools/STRAIGHT_PIN_NAME"]);
+}
+
+#[test]
+fn localised_usages() {
+    let mut index = LocalisedIndex::default();
+    let tool = json!({
+        "m_Name": "Straight Pin",
+        "displayName": { "Sheet": "Tools", "Key": "STRAIGHT_PIN_NAME" },
+        "togglePromptText": { "Sheet": "", "Key": "" },
+        "customButtonCombo": { "PromptText": { "Sheet": "Tools", "Key": "STRAIGHT_PIN_NAME" } },
+    });
+    index.add_value("toolitems.bundle", "ToolItemBasic", &tool);
+
+    let fields: Vec<_> = index
+        .usages("tools", "STRAIGHT_PIN_NAME")
+        .iter()
+        .map(|usage| usage.field.as_str())
+        .collect();
+    assert_eq!(fields, ["customButtonCombo.PromptText", "displayName"]);
+    assert!(index.usages("tools", "ZAP_NAME").is_empty());
+}
+
+#[test]
+fn localised_orphans() {
+    let language: Language = fixture("language.json");
+
+    let mut index = LocalisedIndex::default();
+    let quest = json!({
+        "m_Name": "Pin Quest",
+        "requiredToolsDescription": {
+            "Plural": { "Sheet": "Tools", "Key": "STRAIGHT_PIN_NAME" },
+            "Single": { "Sheet": "Tools", "Key": "MISSING_KEY" },
+        },
+    });
+    index.add_value("quests.bundle", "FullQuestBase", &quest);
+
+    assert_snapshot("localised.json", &to_json(&index));
+    assert_snapshot(
+        "localised_orphans.json",
+        &to_json(&index.orphans(&language)),
+    );
+    assert_eq!(index.unresolved(&language), [("tools", "MISSING_KEY")]);
+}
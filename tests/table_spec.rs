@@ -0,0 +1,102 @@
+mod common;
+
+use anyhow::bail;
+use common::{fixture, to_csv};
+use serde_json::json;
+use silksong_data_dump::lang::Language;
+use silksong_data_dump::table_spec::{TableSpecs, lookup};
+
+#[test]
+fn example_spec_parses() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tables.json");
+    let specs = TableSpecs::read(&path).unwrap();
+    assert!(!specs.tables.is_empty());
+}
+
+#[test]
+fn evaluate_columns() {
+    let language: Language = fixture("language.json");
+    let specs: TableSpecs = serde_json::from_value(json!({
+        "tables": [{
+            "name": "tools",
+            "bundle": "toolitems.bundle",
+            "script": "Tool",
+            "contains": true,
+            "columns": [
+                { "name": "name", "path": "displayName", "localised": true },
+                { "name": "silk", "path": "usageOptions.SilkRequired" },
+                { "name": "firstTest", "path": "alternateUnlockedTest.TestGroups[0].Tests[0].FieldName" },
+                { "name": "cost", "path": "costReference", "deref": "value" },
+                { "name": "flags", "path": "flags" },
+            ]
+        }]
+    }))
+    .unwrap();
+    let spec = &specs.tables[0];
+    let tool = |cost_path_id: i64| {
+        json!({
+            "m_Name": "Straight Pin",
+            "displayName": { "Sheet": "Tools", "Key": "STRAIGHT_PIN_NAME" },
+            "usageOptions": { "SilkRequired": 1 },
+            "alternateUnlockedTest": { "TestGroups": [{ "Tests": [{ "FieldName": "hasPin" }] }] },
+            "costReference": { "m_FileID": 0, "m_PathID": cost_path_id },
+            "flags": [1, 2],
+        })
+    };
+
+    let mut deref = |pptr: rabex_env::rabex::objects::PPtr| match pptr.m_PathID {
+        5 => Ok(json!({ "m_Name": "Cost", "value": 80 })),
+        other => bail!("no object {other}"),
+    };
+    let mut warnings = Vec::new();
+    let mut unknown_name = tool(5);
+    unknown_name["displayName"]["Key"] = json!("UNKNOWN");
+    let rows = [
+        spec.header().iter().map(|s| s.to_string()).collect(),
+        spec.row(&tool(5), &language, &mut deref, &mut warnings),
+        spec.row(&tool(0), &language, &mut deref, &mut warnings),
+    ];
+    assert_eq!(
+        to_csv(rows),
+        "name,silk,firstTest,cost,flags\n\
+         Straight Pin,1,hasPin,80,\"[1,2]\"\n\
+         Straight Pin,1,hasPin,,\"[1,2]\"\n"
+    );
+    assert_eq!(warnings, []);
+
+    // cells which can't be evaluated are left empty
+    let rows = [
+        spec.row(&tool(7), &language, &mut deref, &mut warnings),
+        spec.row(&unknown_name, &language, &mut deref, &mut warnings),
+        spec.row(
+            &json!({ "m_Name": "Empty" }),
+            &language,
+            &mut deref,
+            &mut warnings,
+        ),
+    ];
+    assert_eq!(
+        to_csv(rows),
+        "Straight Pin,1,hasPin,,\"[1,2]\"\n\
+         ,1,hasPin,80,\"[1,2]\"\n\
+         ,,,,\n"
+    );
+    let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        warnings[..3],
+        [
+            "Straight Pin: table 'tools', column 'cost': no object 7",
+            "Straight Pin: table 'tools', column 'name': unknown localised string Tools/UNKNOWN",
+            "Empty: table 'tools', column 'name': no field 'displayName'",
+        ]
+    );
+    assert_eq!(warnings.len(), 7);
+}
+
+#[test]
+fn field_paths() {
+    let value = json!({ "a": { "b": [[1, 2], [3]] } });
+    assert_eq!(lookup(&value, "a.b[1][0]"), Some(&json!(3)));
+    assert_eq!(lookup(&value, "a.b[2]"), None);
+    assert_eq!(lookup(&value, "a.c"), None);
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_snapshot, fixture, to_csv};
+use silksong_data_dump::generated::{
+    CollectableItem, CollectableItemRelicType, CostReference, EnemyJournalRecord, IntReference,
+    Quest, SavedItem, ShopItem, ToolItemBasic,
+};
+use silksong_data_dump::lang::Language;
+use silksong_data_dump::tables::*;
+
+#[test]
+fn enemy_journal() {
+    let lang: Language = fixture("language.json");
+    let records: Vec<EnemyJournalRecord> = fixture("journal.json");
+
+    let rows = records
+        .into_iter()
+        .map(|record| EnemyJournalRecordData::new(record, &lang).unwrap());
+    assert_snapshot("enemyjournal.csv", &to_csv(rows));
+}
+
+#[test]
+fn tools() {
+    let lang: Language = fixture("language.json");
+    let tools: Vec<ToolItemBasic> = fixture("tools.json");
+
+    let mut warnings = Vec::new();
+    let rows: Vec<_> = tools
+        .into_iter()
+        .map(|tool| {
+            // the count key of a tool is usually its own item
+            let count_key =
+                saved_item(&tool.m_Name, &tool.displayName.Sheet, &tool.displayName.Key);
+            let display_name = tool_display_name(Some(count_key), &lang);
+            ToolItemData::new(tool, display_name, &mut warnings).unwrap()
+        })
+        .collect();
+    assert_snapshot("tools.csv", &to_csv(rows));
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+#[test]
+fn tool_display_names() {
+    let lang: Language = serde_json::from_value(serde_json::json!({
+        "tools": { "RUINED": "Ruined Tool", "PIN": "Straight Pin" },
+    }))
+    .unwrap();
+
+    let name = |count_key| tool_display_name(count_key, &lang);
+    assert_eq!(
+        name(Some(saved_item("Pin", "Tools", "PIN"))).as_deref(),
+        Some("Straight Pin")
+    );
+    assert_eq!(name(Some(saved_item("Ruined", "Tools", "RUINED"))), None);
+    assert_eq!(name(None), None);
+}
+
+fn saved_item(name: &str, sheet: &str, key: &str) -> SavedItem {
+    serde_json::from_value(serde_json::json!({
+        "m_GameObject": { "m_FileID": 0, "m_PathID": 0 },
+        "m_Enabled": 1,
+        "m_Script": { "m_FileID": 0, "m_PathID": 0 },
+        "m_Name": name,
+        "displayName": { "Sheet": sheet, "Key": key },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn shop_items() {
+    let lang: Language = fixture("language.json");
+    let items: Vec<ShopItem> = fixture("shop_items.json");
+    let mut costs = fixture::<Vec<CostReference>>("costs.json").into_iter();
+    let mut collectables = fixture::<Vec<CollectableItem>>("collectables.json").into_iter();
+    let quest_names = HashMap::from([(41, "Brew Delivery"), (42, "Rosary Debt")]);
+
+    let rows: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let cost_ref = (item.costReference.m_PathID != 0)
+                .then(|| costs.next())
+                .flatten();
+            let required_item = (item.requiredItem.m_PathID != 0)
+                .then(|| collectables.next())
+                .flatten();
+            let quests: Vec<String> = item
+                .questsAppearConditions
+                .iter()
+                .map(|test| quest_names[&test.Quest.m_PathID].to_owned())
+                .collect();
+            ShopItemData::new(item, &lang, cost_ref, required_item, &quests).unwrap()
+        })
+        .collect();
+    assert_snapshot("shopitems.csv", &to_csv(rows));
+}
+
+#[test]
+fn quests() {
+    let lang: Language = fixture("language.json");
+    let quests: Vec<Quest> = fixture("quests.json");
+    let counters = HashMap::from([(51, "Flea Brew"), (52, "Rosary String")]);
+
+    let rows = quests.into_iter().map(|quest| {
+        let reward = (quest.rewardItem.m_PathID != 0).then(|| saved_item("Flea Brew", "", ""));
+        let counters: Vec<String> = quest
+            .targets
+            .iter()
+            .map(|target| {
+                counters
+                    .get(&target.Counter.m_PathID)
+                    .map_or_else(String::new, |&name| name.to_owned())
+            })
+            .collect();
+        QuestData::new(quest, &lang, reward, &counters)
+    });
+    assert_snapshot("quests.csv", &to_csv(rows));
+}
+
+#[test]
+fn missing_localised_strings() {
+    let lang: Language = fixture("language.json");
+    let mut records: Vec<EnemyJournalRecord> = fixture("journal.json");
+    let mut items: Vec<CollectableItemRelicType> = fixture("collectables.json");
+
+    records[0].displayName.Key = "UNKNOWN".to_owned();
+    let error = EnemyJournalRecordData::new(records.remove(0), &lang).unwrap_err();
+    assert!(error.to_string().contains("UNKNOWN"), "{error}");
+
+    items[0].typeName.Sheet = String::new();
+    assert!(CollectableItemData::new(items.remove(0), &lang).is_err());
+
+    let unknown_sheet = saved_item("Pin", "Unknown", "PIN");
+    assert_eq!(tool_display_name(Some(unknown_sheet), &lang), None);
+}
+
+#[test]
+fn silk_required_flag() {
+    let mut tools: Vec<ToolItemBasic> = fixture("tools.json");
+    tools.truncate(1);
+    tools[0].usageOptions.SilkRequired = 2;
+
+    let mut warnings = Vec::new();
+    let row = ToolItemData::new(tools.remove(0), None, &mut warnings).unwrap();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let error = writer.serialize(row).unwrap_err();
+    assert!(error.to_string().contains("2 is not a boolean"), "{error}");
+}
+
+#[test]
+fn collectable_items() {
+    let lang: Language = fixture("language.json");
+    let items: Vec<CollectableItemRelicType> = fixture("collectables.json");
+
+    let rows = items
+        .into_iter()
+        .map(|item| CollectableItemData::new(item, &lang).unwrap());
+    assert_snapshot("collectableitems.csv", &to_csv(rows));
+}
+
+#[test]
+fn int_references() {
+    let costs: Vec<IntReference> = fixture("costs.json");
+
+    assert_snapshot(
+        "costs.csv",
+        &to_csv(costs.into_iter().map(IntReferenceData::from)),
+    );
+}
+
+#[test]
+fn version_column() {
+    #[derive(serde::Serialize)]
+    struct Row {
+        name: &'static str,
+        value: &'static str,
+    }
+    let rows = || {
+        [
+            Row {
+                name: "Pin",
+                value: "1,2",
+            },
+            Row {
+                name: "Needle",
+                value: "3",
+            },
+        ]
+    };
+    let write = |version: Option<&str>| {
+        let mut writer = TableWriter::new(Vec::new(), version);
+        for row in rows() {
+            writer.serialize(row).unwrap();
+        }
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        write(Some("1.0.28324")),
+        "name,value,gameVersion\nPin,\"1,2\",1.0.28324\nNeedle,3,1.0.28324\n"
+    );
+    assert_eq!(write(None), "name,value\nPin,\"1,2\"\nNeedle,3\n");
+
+    // tables written record by record, like the table specs
+    let mut writer = TableWriter::new(Vec::new(), Some("1.0"));
+    writer.write_header(["name"]).unwrap();
+    writer.write_row(vec!["Pin".to_owned()]).unwrap();
+    assert_eq!(
+        String::from_utf8(writer.into_inner().unwrap()).unwrap(),
+        "name,gameVersion\nPin,1.0\n"
+    );
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{assert_snapshot, fixture, to_json};
+use indexmap::IndexMap;
+use silksong_data_dump::lang::Language;
+use silksong_data_dump::po::PoFile;
+use silksong_data_dump::translation::{self, markup_tags, placeholders};
+use silksong_data_dump::xliff::{self, XliffVersion};
+
+#[test]
+fn validate_and_patch() {
+    let mut original: Language = fixture("language.json");
+    original["tools"].insert("TOOL_COUNT".to_owned(), "{0} of {1} tools".to_owned());
+
+    let mut translation: Language = fixture("language.json");
+    translation["tools"].shift_remove("ZAP_NAME");
+    translation["tools"]["STRAIGHT_PIN_NAME"] = "Aiguille droite".to_owned();
+    translation["tools"].insert("TOOL_COUNT".to_owned(), "{0} outils".to_owned());
+    translation["tools"].insert("UNKNOWN_KEY".to_owned(), "?".to_owned());
+
+    let issues = translation::validate(&original, &translation);
+    assert_snapshot("translation_issues.json", &to_json(&issues));
+
+    let patched = translation::patch(&original, &translation);
+    assert_eq!(patched["tools"]["STRAIGHT_PIN_NAME"], "Aiguille droite");
+    assert_eq!(patched["tools"]["ZAP_NAME"], "Volt Filament");
+    assert!(!patched["tools"].contains_key("UNKNOWN_KEY"));
+}
+
+#[test]
+fn placeholder_extraction() {
+    assert_eq!(placeholders("{0} of {1}"), ["{0}", "{1}"]);
+    assert_eq!(placeholders("{{0}}, { not one }, {"), ["{0}"]);
+}
+
+#[test]
+fn read_po() {
+    let po = r#"
+msgid ""
+msgstr ""
+"Language: FR\n"
+
+#: journal
+msgctxt "journal/NAME_MOSSGRUB"
+msgid "Mossgrub"
+msgstr "Larve "
+"moussue"
+
+msgctxt "tools/TOOL_DESC"
+msgid "Line one\nLine \"two\""
+msgstr ""
+"#;
+    let file = PoFile::parse(po).unwrap();
+    assert_eq!(file.language.as_deref(), Some("FR"));
+    assert_eq!(file.entries[1].id, "Line one\nLine \"two\"");
+
+    let languages = translation::read_po(po, None).unwrap();
+    assert_eq!(languages["FR"]["journal"]["NAME_MOSSGRUB"], "Larve moussue");
+    assert!(!languages["FR"].contains_key("tools"));
+}
+
+fn language_pair() -> (Language, Language) {
+    let source: Language = fixture("language.json");
+    let mut target = source.clone();
+    target["tools"]["STRAIGHT_PIN_NAME"] = "Aiguille \"droite\"".to_owned();
+    target["journal"]["NAME_MOSSGRUB"] = "Larve moussue\n<page>".to_owned();
+    target["journal"].shift_remove("NAME_HORNET");
+    (source, target)
+}
+
+#[test]
+fn export_po() {
+    let (source, target) = language_pair();
+    let po = translation::to_po("FR", &source, &target).write();
+    assert_snapshot("FR.po", &po);
+
+    let imported = translation::read_po(&po, None).unwrap();
+    assert_eq!(imported["FR"]["tools"], target["tools"]);
+    assert_eq!(imported["FR"]["journal"], target["journal"]);
+}
+
+#[test]
+fn export_xliff() {
+    let (source, target) = language_pair();
+    for (version, name) in [
+        (XliffVersion::V1_2, "FR-1.2.xlf"),
+        (XliffVersion::V2_0, "FR-2.0.xlf"),
+    ] {
+        let xliff = xliff::write(version, ("EN", &source), ("FR", &target));
+        assert_snapshot(name, &xliff);
+    }
+}
+
+#[test]
+fn coverage_report() {
+    let mut reference: Language = fixture("language.json");
+    reference["tools"].insert("TOOL_COUNT".to_owned(), "{0} of {1}<br>tools".to_owned());
+    reference["tools"].insert("EMPTY".to_owned(), String::new());
+
+    let (_, mut target) = language_pair();
+    target["tools"].insert("TOOL_COUNT".to_owned(), "{0} outils".to_owned());
+    target["tools"]["POISON_POUCH_NAME"] = String::new();
+
+    let report = IndexMap::from([("FR".to_owned(), translation::coverage(&reference, &target))]);
+    assert_snapshot("translation_report.json", &to_json(&report));
+    assert_snapshot(
+        "translation_report.md",
+        &translation::coverage_markdown(&report),
+    );
+}
+
+#[test]
+fn markup_tag_extraction() {
+    assert_eq!(markup_tags("a<br>b <page> 1 < 2 > 0"), ["<br>", "<page>"]);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::fixture;
+use serde_json::{Value, json};
+use silksong_data_dump::lang::Language;
+use silksong_data_dump::typetree::{as_localised, as_pptr, map_pptrs, resolve_localised, visit};
+
+#[test]
+fn resolve_typetree_values() {
+    let language: Language = fixture("language.json");
+    let mut value = json!({
+        "m_Name": "Straight Pin",
+        "m_Script": { "m_FileID": 0, "m_PathID": 12 },
+        "countKey": { "m_FileID": 0, "m_PathID": 0 },
+        "displayName": { "Sheet": "Tools", "Key": "STRAIGHT_PIN_NAME" },
+        "description": { "Sheet": "Tools", "Key": "UNKNOWN" },
+        "togglePromptText": { "Sheet": "", "Key": "" },
+        "extra": [{ "m_FileID": 1, "m_PathID": -5 }],
+    });
+
+    map_pptrs(&mut value, &mut |pptr| {
+        Value::String(format!("{}:{}", pptr.m_FileID, pptr.m_PathID))
+    });
+    resolve_localised(&mut value, &language);
+
+    assert_eq!(
+        value,
+        json!({
+            "m_Name": "Straight Pin",
+            "m_Script": "0:12",
+            "countKey": null,
+            "displayName": "Straight Pin",
+            "description": { "Sheet": "Tools", "Key": "UNKNOWN" },
+            "togglePromptText": null,
+            "extra": ["1:-5"],
+        })
+    );
+}
+
+#[test]
+fn visit_paths() {
+    let value = json!({
+        "m_Script": { "m_FileID": 1, "m_PathID": 2 },
+        "targets": [{ "Counter": { "m_FileID": 0, "m_PathID": 5 } }],
+        "name": { "Sheet": "Tools", "Key": "PIN" },
+    });
+    let mut pptrs = Vec::new();
+    visit(&value, &mut |path, value| match as_pptr(value) {
+        Some(pptr) => {
+            pptrs.push((path.to_owned(), pptr.m_PathID));
+            false
+        }
+        None => true,
+    });
+    assert_eq!(
+        pptrs,
+        [
+            ("m_Script".to_owned(), 2),
+            ("targets[0].Counter".to_owned(), 5)
+        ]
+    );
+    assert_eq!(as_localised(&value["name"]), Some(("Tools", "PIN")));
+    assert_eq!(as_localised(&value["targets"]), None);
+}